@@ -0,0 +1,211 @@
+use crate::commands::project_commands::{configure_command_env, run_streamed};
+use crate::utils::project_location;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComposerPackage {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComposerDependencies {
+    pub require: Vec<ComposerPackage>,
+    pub require_dev: Vec<ComposerPackage>,
+    /// True when versions are the exact ones from composer.lock, false when they are the
+    /// constraints from composer.json
+    pub locked: bool,
+}
+
+#[derive(Deserialize)]
+struct ComposerLock {
+    #[serde(default)]
+    packages: Vec<ComposerPackage>,
+    #[serde(default, rename = "packages-dev")]
+    packages_dev: Vec<ComposerPackage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutdatedPackage {
+    pub name: String,
+    pub version: String,
+    pub latest: String,
+    /// Newest release that is semver-compatible with the installed version
+    pub latest_semver: String,
+    /// Composer's verdict: "semver-safe-update", "update-possible" or "up-to-date"
+    pub latest_status: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ComposerOutdated {
+    #[serde(default)]
+    installed: Vec<ComposerOutdatedEntry>,
+}
+
+#[derive(Deserialize)]
+struct ComposerOutdatedEntry {
+    name: String,
+    version: String,
+    #[serde(default)]
+    latest: String,
+    #[serde(default, rename = "latest-status")]
+    latest_status: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ComposerJson {
+    #[serde(default)]
+    require: BTreeMap<String, String>,
+    #[serde(default, rename = "require-dev")]
+    require_dev: BTreeMap<String, String>,
+}
+
+/// Looks up a project's folder and checks that it has a composer.json.
+fn composer_project_location(project_id: &str) -> Result<PathBuf, String> {
+    let location = project_location(project_id)?;
+    if !location.join("composer.json").exists() {
+        return Err("composer.json not found".to_string());
+    }
+    Ok(location)
+}
+
+fn composer_not_found(e: std::io::Error) -> String {
+    format!(
+        "Failed to execute composer: {}. Make sure Composer is installed and in your PATH.",
+        e
+    )
+}
+
+/// Runs `composer <args>` to completion and captures its output.
+fn composer_output(location: &Path, args: &[&str]) -> Result<std::process::Output, String> {
+    let mut cmd = std::process::Command::new("composer");
+    cmd.current_dir(location).args(args);
+    configure_command_env(&mut cmd);
+    cmd.output().map_err(composer_not_found)
+}
+
+/// Runs `composer outdated` with `args` and parses its JSON report. Composer prints
+/// warnings (deprecations, a missing lock file...) on stderr even when it succeeds, so only
+/// stdout is parsed and stderr only matters if that fails.
+fn composer_outdated(location: &Path, args: &[&str]) -> Result<Vec<ComposerOutdatedEntry>, String> {
+    let mut full_args = vec!["outdated", "--direct", "--format=json", "--no-interaction"];
+    full_args.extend_from_slice(args);
+    let output = composer_output(location, &full_args)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match serde_json::from_str::<ComposerOutdated>(stdout.trim()) {
+        Ok(report) => Ok(report.installed),
+        Err(e) if output.status.success() => {
+            Err(format!("Failed to parse composer outdated output: {}", e))
+        }
+        Err(_) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+/// Runs `composer <args>` in the project folder, streaming output to
+/// `composer-output-<project_id>`. Returns the exit code.
+#[command(async)]
+pub fn run_composer(
+    project_id: String,
+    args: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<i32, String> {
+    let location = composer_project_location(&project_id)?;
+
+    let mut cmd = std::process::Command::new("composer");
+    cmd.current_dir(&location).args(&args);
+
+    // Only one composer command per project; two would fight over vendor/ and composer.lock
+    run_streamed(
+        cmd,
+        &project_id,
+        "composer".to_string(),
+        &format!("composer-output-{}", project_id),
+        &app_handle,
+        composer_not_found,
+    )
+}
+
+/// Lists the project's packages, with exact versions from composer.lock when it exists and
+/// the composer.json constraints otherwise.
+#[command]
+pub fn get_composer_dependencies(project_id: String) -> Result<ComposerDependencies, String> {
+    let location = composer_project_location(&project_id)?;
+
+    let lock_path = location.join("composer.lock");
+    if std::path::Path::new(&lock_path).exists() {
+        let content = std::fs::read_to_string(&lock_path).map_err(|e| e.to_string())?;
+        let lock: ComposerLock = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse composer.lock: {}", e))?;
+        return Ok(ComposerDependencies {
+            require: lock.packages,
+            require_dev: lock.packages_dev,
+            locked: true,
+        });
+    }
+
+    let json_path = location.join("composer.json");
+    let content = std::fs::read_to_string(&json_path).map_err(|e| e.to_string())?;
+    let json: ComposerJson = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse composer.json: {}", e))?;
+    let packages = |deps: BTreeMap<String, String>| {
+        deps.into_iter()
+            .map(|(name, version)| ComposerPackage { name, version })
+            .collect()
+    };
+    Ok(ComposerDependencies {
+        require: packages(json.require),
+        require_dev: packages(json.require_dev),
+        locked: false,
+    })
+}
+
+/// Lists the project's direct dependencies that have newer releases.
+#[command(async)]
+pub fn get_outdated_packages(project_id: String) -> Result<Vec<OutdatedPackage>, String> {
+    let location = composer_project_location(&project_id)?;
+    let installed = composer_outdated(&location, &[])?;
+
+    // For packages whose latest release is a major bump, ask again for the newest
+    // semver-compatible release; this second run is only needed when there are any
+    let semver = if installed
+        .iter()
+        .any(|p| p.latest_status == "update-possible")
+    {
+        composer_outdated(&location, &["--minor-only"])?
+            .into_iter()
+            .map(|p| (p.name, p.latest))
+            .collect()
+    } else {
+        BTreeMap::new()
+    };
+
+    Ok(installed
+        .into_iter()
+        .map(|p| {
+            // --minor-only leaves out packages with no compatible update at all
+            let latest_semver = match p.latest_status.as_str() {
+                "semver-safe-update" => p.latest.clone(),
+                "update-possible" => semver
+                    .get(&p.name)
+                    .cloned()
+                    .unwrap_or_else(|| p.version.clone()),
+                _ => p.version.clone(),
+            };
+            OutdatedPackage {
+                name: p.name,
+                version: p.version,
+                latest: p.latest,
+                latest_semver,
+                latest_status: p.latest_status,
+                description: p.description,
+            }
+        })
+        .collect())
+}
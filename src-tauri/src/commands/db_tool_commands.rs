@@ -1,53 +1,231 @@
+use crate::credentials::{
+    decrypt_password, delete_keychain_password, is_encrypted, keychain_password, protect_password,
+};
 use crate::database::Database;
-use crate::db_factory::{get_db_backend, DbBackend};
-use crate::models::db_types::{DbCredentials, TableData};
-use crate::state::DbConnectionManager;
-use crate::utils::get_db_path;
-use std::collections::HashMap;
+use crate::db_factory::{
+    get_db_backend, is_transaction_control, split_sql_script, split_sql_statements, Backend,
+    DbBackend, KeyValueBackend,
+};
+use crate::error::{CommandError, CommandResult};
+use crate::models::db_types::{
+    ColumnDefinition, ColumnFilter, ColumnStats, ConnectionInfo, DbCredentials, DumpProgress,
+    ForeignKey, IndexInfo, NamedConnection, RedisKeys, RedisValue, RowCount, RowFilter,
+    SqlScriptResult, TableData, DEFAULT_CONNECTION,
+};
+use crate::models::project::Project;
+use crate::state::{
+    CachedConnection, CachedCredentials, DbConnectionManager, EnvStamp, SharedBackend,
+};
+use crate::utils::{get_db_path, parse_env};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::{BufWriter, Write};
 use std::path::Path;
-use tauri::{command, State};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, State};
 
-fn create_db_backend(project_id: &str) -> Result<Box<dyn DbBackend + Send>, String> {
-    // Get project location
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path)
-        .map_err(|e| format!("Failed to connect to projects database: {}", e))?;
-
-    let project = match db.get_project_by_id(project_id) {
-        Ok(Some(project)) => project,
-        Ok(None) => {
-            return Err(format!(
-                "Project with ID '{}' not found in database",
-                project_id
-            ))
-        }
-        Err(e) => return Err(format!("Database error while fetching project: {}", e)),
+/// Largest page the table browsing commands return; bigger requests are clamped to it so a
+/// huge `per_page` can't pull a whole table into memory.
+const MAX_PER_PAGE: u32 = 1000;
+
+/// Tables whose estimated size is below this are counted exactly; `COUNT(*)` is cheap there.
+const EXACT_COUNT_THRESHOLD: u64 = 100_000;
+
+fn clamp_per_page(per_page: u32) -> CommandResult<u32> {
+    if per_page == 0 {
+        return Err(CommandError::InvalidInput(
+            "Invalid page size 0: per_page must be at least 1".to_string(),
+        ));
+    }
+    Ok(per_page.min(MAX_PER_PAGE))
+}
+
+/// Checks paging arguments before they reach a backend. Returns the clamped page size.
+fn validate_paging(page: u32, per_page: u32) -> CommandResult<u32> {
+    if page == 0 {
+        return Err(CommandError::InvalidInput(
+            "Invalid page number 0: pages start at 1".to_string(),
+        ));
+    }
+    clamp_per_page(per_page)
+}
+
+/// Key for the connection and credential caches.
+fn cache_key(project_id: &str, connection: &str) -> String {
+    format!("{}/{}", project_id, connection)
+}
+
+/// Keychain id for a connection's password. The default connection keeps using the project
+/// id, so passwords saved before named connections existed are still found.
+fn secret_id(project_id: &str, connection: &str) -> String {
+    if connection == DEFAULT_CONNECTION {
+        project_id.to_string()
+    } else {
+        format!("{}/{}", project_id, connection)
+    }
+}
+
+/// Opens a backend for one of the project's connections with its current credentials.
+fn open_connection(
+    state: &DbConnectionManager,
+    project_id: &str,
+    connection: &str,
+) -> CommandResult<CachedConnection> {
+    let cached = project_credentials(state, project_id, connection)?;
+    let backend = get_db_backend(&cached.credentials, &cached.location)?;
+    Ok(CachedConnection {
+        canceller: match &backend {
+            Backend::Sql(backend) => backend.canceller(),
+            Backend::KeyValue(_) => None,
+        },
+        backend: Arc::new(Mutex::new(backend)),
+        info: ConnectionInfo::from(&cached.credentials),
+        env: cached.env,
+    })
+}
+
+/// A connection's resolved credentials, from the cache unless `.env` changed since they were
+/// resolved. `save_db_credentials` clears the cache entry.
+fn project_credentials(
+    state: &DbConnectionManager,
+    project_id: &str,
+    connection: &str,
+) -> CommandResult<CachedCredentials> {
+    let key = cache_key(project_id, connection);
+    let mut cache = state
+        .credentials
+        .lock()
+        .map_err(|e| CommandError::Other(e.to_string()))?;
+    if let Some(cached) = cache.get(&key).filter(|cached| !cached.env.is_stale()) {
+        return Ok(cached.clone());
+    }
+
+    let project = find_project(project_id)?;
+    // Taken before resolving so an edit made meanwhile is caught on the next call
+    let env = EnvStamp::new(Path::new(&project.location).join(".env"));
+    let credentials = resolve_db_credentials(&project, connection)?;
+    tracing::debug!(
+        project_id,
+        connection_name = connection,
+        connection = %credentials.connection,
+        "Resolved database credentials"
+    );
+    let cached = CachedCredentials {
+        credentials,
+        location: project.location,
+        env,
     };
+    cache.insert(key, cached.clone());
+    Ok(cached)
+}
+
+/// Drops a connection's cached backend and credentials so the next use resolves them again.
+fn forget_connection(
+    state: &DbConnectionManager,
+    project_id: &str,
+    connection: &str,
+) -> CommandResult<()> {
+    let key = cache_key(project_id, connection);
+    state
+        .connections
+        .lock()
+        .map_err(|e| CommandError::Other(e.to_string()))?
+        .remove(&key);
+    state
+        .credentials
+        .lock()
+        .map_err(|e| CommandError::Other(e.to_string()))?
+        .remove(&key);
+    Ok(())
+}
+
+/// Connections saved in `db_config`, keyed by name.
+fn saved_connections(project: &Project) -> BTreeMap<String, DbCredentials> {
+    project
+        .db_config
+        .as_deref()
+        .and_then(|config| serde_json::from_str(config).ok())
+        .unwrap_or_default()
+}
 
+/// Removes the keychain passwords of every connection saved for the project.
+pub(crate) fn delete_saved_passwords(project: &Project) {
+    for connection in saved_connections(project).keys() {
+        delete_keychain_password(&secret_id(&project.id, connection));
+    }
+}
+
+fn store_connections(
+    project: &Project,
+    connections: &BTreeMap<String, DbCredentials>,
+) -> CommandResult<()> {
+    let mut project = project.clone();
+    project.db_config = Some(
+        serde_json::to_string(connections)
+            .map_err(|e| format!("Failed to serialize credentials: {}", e))?,
+    );
+    projects_database()?
+        .update_project(&project.id, &project)
+        .map_err(|e| CommandError::Database(format!("Failed to update project: {}", e)))
+}
+
+/// Works out a connection's credentials. Other connections than the default only exist in
+/// `db_config`; see `find_default_credentials` for the default one.
+fn resolve_db_credentials(project: &Project, connection: &str) -> CommandResult<DbCredentials> {
+    if connection != DEFAULT_CONNECTION {
+        let config = saved_connections(project)
+            .remove(connection)
+            .ok_or_else(|| {
+                CommandError::NotFound(format!("Database connection '{}' not found.", connection))
+            })?;
+        return unlock_credentials(project, connection, config);
+    }
+
+    let saved = saved_connections(project).remove(DEFAULT_CONNECTION);
+    match find_default_credentials(Path::new(&project.location), saved)? {
+        Some(DefaultCredentials::Saved(config)) => {
+            unlock_credentials(project, DEFAULT_CONNECTION, config)
+        }
+        Some(DefaultCredentials::Env(creds) | DefaultCredentials::ProjectJson(creds)) => Ok(creds),
+        None => Err(CommandError::NotFound(
+            "Database configuration not found.".to_string(),
+        )),
+    }
+}
+
+/// Where the default connection's credentials were found.
+#[derive(Debug)]
+enum DefaultCredentials {
+    Env(DbCredentials),
+    Saved(DbCredentials), // As stored, with the password still locked
+    ProjectJson(DbCredentials),
+}
+
+/// Picks the default connection's credentials for the project folder: `.env` first, then the
+/// saved `db_config` entry, then the legacy `.workshop/project.json`. Only reads those files;
+/// unlocking a saved password is up to the caller.
+fn find_default_credentials(
+    location: &Path,
+    saved: Option<DbCredentials>,
+) -> CommandResult<Option<DefaultCredentials>> {
+    if let Some(creds) = env_credentials(location)? {
+        return Ok(Some(DefaultCredentials::Env(creds)));
+    }
+    if let Some(config) = saved {
+        return Ok(Some(DefaultCredentials::Saved(config)));
+    }
+    Ok(project_json_credentials(location).map(DefaultCredentials::ProjectJson))
+}
+
+/// Credentials from the project's `.env`, as Laravel's `DB_*` keys or a connection URL.
+fn env_credentials(location: &Path) -> CommandResult<Option<DbCredentials>> {
     let mut creds: Option<DbCredentials> = None;
 
-    // 1. Try .env file first (for Laravel or other dotenv projects)
-    let env_path = Path::new(&project.location).join(".env");
+    let env_path = location.join(".env");
 
     if env_path.exists() {
         if let Ok(env_content) = fs::read_to_string(&env_path) {
-            let mut env_vars = HashMap::new();
-            for line in env_content.lines() {
-                if line.trim().is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some((key, value)) = line.split_once('=') {
-                    // Handle quoted values simply
-                    let val = value.trim();
-                    let val = if val.starts_with('"') && val.ends_with('"') {
-                        &val[1..val.len() - 1]
-                    } else {
-                        val
-                    };
-                    env_vars.insert(key.trim().to_string(), val.to_string());
-                }
-            }
+            let env_vars = parse_env(&env_content);
 
             let get_env =
                 |key: &str| -> Option<String> { env_vars.get(key).map(|s| s.to_string()) };
@@ -68,6 +246,8 @@ fn create_db_backend(project_id: &str) -> Result<Box<dyn DbBackend + Send>, Stri
                             database: d,
                             username: Some(u),
                             password: Some(pw),
+                            socket: get_env("DB_SOCKET").filter(|s| !s.is_empty()),
+                            ..Default::default()
                         });
                     }
                 } else if conn == "sqlite" {
@@ -79,267 +259,1115 @@ fn create_db_backend(project_id: &str) -> Result<Box<dyn DbBackend + Send>, Stri
                             database: d,
                             username: None,
                             password: None,
+                            // Laravel's own DB_FOREIGN_KEYS setting for SQLite
+                            foreign_keys: get_env("DB_FOREIGN_KEYS").map(|v| v != "false"),
+                            ..Default::default()
                         });
                     }
                 }
             }
-        }
-    }
 
-    // 2. If not found in .env, try project.db_config (internal DB)
-    if creds.is_none() {
-        if let Some(config_str) = &project.db_config {
-            if let Ok(config) = serde_json::from_str::<DbCredentials>(config_str) {
-                creds = Some(config);
+            // Frameworks that ship a single connection URL instead of discrete DB_* keys
+            if creds.is_none() {
+                if let Some(url) = get_env("DATABASE_URL").or_else(|| get_env("DB_URL")) {
+                    creds =
+                        Some(DbCredentials::from_url(&url).map_err(CommandError::InvalidInput)?);
+                }
             }
         }
     }
 
-    // Fallback: Check .workshop/project.json
-    if creds.is_none() {
-        let project_json_path = Path::new(&project.location)
-            .join(".workshop")
-            .join("project.json");
-        if project_json_path.exists() {
-            if let Ok(content) = fs::read_to_string(&project_json_path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(db_config) = json.get("database") {
-                        let connection = db_config["connection"].as_str().unwrap_or("").to_string();
-                        if connection == "mysql" {
-                            creds = Some(DbCredentials {
-                                connection,
-                                host: db_config["host"].as_str().map(|s| s.to_string()),
-                                port: db_config["port"].as_str().map(|s| s.to_string()),
-                                database: db_config["database"].as_str().unwrap_or("").to_string(),
-                                username: db_config["username"].as_str().map(|s| s.to_string()),
-                                password: db_config["password"].as_str().map(|s| s.to_string()),
-                            });
-                        }
-                    }
-                }
-            }
-        }
+    Ok(creds)
+}
+
+/// Credentials from the legacy `database` section of `.workshop/project.json`.
+fn project_json_credentials(location: &Path) -> Option<DbCredentials> {
+    let content = fs::read_to_string(location.join(".workshop").join("project.json")).ok()?;
+    let json = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    let db_config = json.get("database")?;
+    if db_config["connection"].as_str() != Some("mysql") {
+        return None;
+    }
+    Some(DbCredentials {
+        connection: "mysql".to_string(),
+        host: db_config["host"].as_str().map(|s| s.to_string()),
+        port: db_config["port"].as_str().map(|s| s.to_string()),
+        database: db_config["database"].as_str().unwrap_or("").to_string(),
+        username: db_config["username"].as_str().map(|s| s.to_string()),
+        password: db_config["password"].as_str().map(|s| s.to_string()),
+        socket: db_config["socket"].as_str().map(|s| s.to_string()),
+        ..Default::default()
+    })
+}
+
+fn projects_database() -> CommandResult<Database> {
+    let db_path = get_db_path()?;
+    Database::new(db_path).map_err(|e| {
+        CommandError::Database(format!("Failed to connect to projects database: {}", e))
+    })
+}
+
+fn find_project(project_id: &str) -> CommandResult<Project> {
+    match projects_database()?.get_project_by_id(project_id) {
+        Ok(Some(project)) => Ok(project),
+        Ok(None) => Err(CommandError::NotFound(format!(
+            "Project with ID '{}' not found in database",
+            project_id
+        ))),
+        Err(e) => Err(CommandError::Database(format!(
+            "Database error while fetching project: {}",
+            e
+        ))),
     }
+}
+
+/// Fills in a saved connection's password from the keychain or its encrypted form.
+fn unlock_credentials(
+    project: &Project,
+    connection: &str,
+    mut config: DbCredentials,
+) -> CommandResult<DbCredentials> {
+    // Configs saved before passwords were protected get migrated on first read
+    if config
+        .password
+        .as_deref()
+        .is_some_and(|pw| !is_encrypted(pw))
+    {
+        migrate_plaintext_password(project, connection, &config)?;
+    }
+    config.password = match &config.password {
+        Some(password) => Some(decrypt_password(password)?),
+        None => keychain_password(&secret_id(&project.id, connection)),
+    };
+    Ok(config)
+}
+
+/// Rewrites a legacy saved connection so its password lives in the keychain (or encrypted).
+fn migrate_plaintext_password(
+    project: &Project,
+    connection: &str,
+    config: &DbCredentials,
+) -> CommandResult<()> {
+    let mut protected = config.clone();
+    if let Some(password) = &config.password {
+        protected.password = protect_password(&secret_id(&project.id, connection), password)?;
+    }
+
+    let mut connections = saved_connections(project);
+    connections.insert(connection.to_string(), protected);
+    store_connections(project, &connections)
+}
+
+/// Runs blocking work (database and file I/O) on Tauri's blocking thread pool, so a slow
+/// query doesn't hold up the async runtime or freeze the UI.
+async fn run_blocking<F, R>(f: F) -> CommandResult<R>
+where
+    F: FnOnce() -> CommandResult<R> + Send + 'static,
+    R: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| CommandError::Other(e.to_string()))?
+}
 
-    if let Some(c) = creds {
-        // Here we ensure the backend is Send (impl DbBackend + Send)
-        // But get_db_backend returns Box<dyn DbBackend>. We need to cast or rely on coherence?
-        // Let's modify get_db_backend signature in db_factory?
-        // Or assume it returns Send?
-        // db_factory::get_db_backend returns Result<Box<dyn DbBackend>, String>
-        // Use a cast or wrapper? Box<dyn DbBackend> is not necessarily Box<dyn DbBackend + Send>.
-        // I need to change db_factory logic or return type.
-        // Actually, I'll update db_factory to return Box<dyn DbBackend + Send>.
-        return get_db_backend(&c, &project.location);
+/// The cached backend for a connection of the project, opened on first use. The map is only
+/// locked to look up and insert, never while connecting or querying, so an unreachable host
+/// or a long query doesn't block other projects' connections.
+fn connection_backend(
+    state: &DbConnectionManager,
+    project_id: &str,
+    connection: &str,
+) -> CommandResult<SharedBackend> {
+    let key = cache_key(project_id, connection);
+    {
+        let mut connections = state
+            .connections
+            .lock()
+            .map_err(|e| CommandError::Other(e.to_string()))?;
+        if connections.get(&key).is_some_and(|c| c.env.is_stale()) {
+            tracing::info!(project_id, "`.env` changed, reconnecting to the database");
+            connections.remove(&key);
+        }
+        if let Some(opened) = connections.get(&key) {
+            return Ok(Arc::clone(&opened.backend));
+        }
     }
 
-    Err("Database configuration not found.".to_string())
+    tracing::debug!(
+        project_id,
+        connection_name = connection,
+        "Opening a new database connection"
+    );
+    let opened = open_connection(state, project_id, connection)?;
+    // Another command may have connected meanwhile; keep whichever got there first
+    let mut connections = state
+        .connections
+        .lock()
+        .map_err(|e| CommandError::Other(e.to_string()))?;
+    let opened = connections.entry(key).or_insert(opened);
+    Ok(Arc::clone(&opened.backend))
 }
 
-fn with_db_backend<F, R>(
-    state: &State<DbConnectionManager>,
+/// Runs `f` against a connection of the project on the blocking pool, opening the
+/// connection on first use. `connection` defaults to the project's primary connection.
+async fn with_backend<F, R>(
+    state: &Arc<DbConnectionManager>,
     project_id: &str,
+    connection: Option<String>,
     f: F,
-) -> Result<R, String>
+) -> CommandResult<R>
 where
-    F: FnOnce(&mut Box<dyn DbBackend + Send>) -> Result<R, String>,
+    F: FnOnce(&mut Backend, &str) -> CommandResult<R> + Send + 'static,
+    R: Send + 'static,
 {
-    let mut connections = state.connections.lock().map_err(|e| e.to_string())?;
+    let state = Arc::clone(state);
+    let project_id = project_id.to_string();
+    let connection = connection.unwrap_or(DEFAULT_CONNECTION.to_string());
+    run_blocking(move || {
+        let backend = connection_backend(&state, &project_id, &connection)?;
+        let mut backend = backend
+            .lock()
+            .map_err(|e| CommandError::Other(e.to_string()))?;
+        f(&mut backend, &connection)
+    })
+    .await
+}
 
-    if !connections.contains_key(project_id) {
-        let backend = create_db_backend(project_id)?;
-        connections.insert(project_id.to_string(), backend);
-    }
+/// Like `with_backend`, for the SQL commands; fails with `Unsupported` on Redis connections.
+async fn with_db_backend<F, R>(
+    state: &Arc<DbConnectionManager>,
+    project_id: &str,
+    connection: Option<String>,
+    f: F,
+) -> CommandResult<R>
+where
+    F: FnOnce(&mut Box<dyn DbBackend + Send>) -> Result<R, String> + Send + 'static,
+    R: Send + 'static,
+{
+    with_backend(
+        state,
+        project_id,
+        connection,
+        |backend, name| match backend {
+            Backend::Sql(backend) => f(backend).map_err(CommandError::Database),
+            Backend::KeyValue(_) => Err(CommandError::Unsupported(format!(
+                "Connection '{}' is a Redis connection, which has no tables or SQL",
+                name
+            ))),
+        },
+    )
+    .await
+}
+
+/// Like `with_backend`, for the key/value commands; fails with `Unsupported` when the
+/// connection is a SQL database.
+async fn with_key_value_backend<F, R>(
+    state: &Arc<DbConnectionManager>,
+    project_id: &str,
+    connection: Option<String>,
+    f: F,
+) -> CommandResult<R>
+where
+    F: FnOnce(&mut Box<dyn KeyValueBackend + Send>) -> Result<R, String> + Send + 'static,
+    R: Send + 'static,
+{
+    with_backend(
+        state,
+        project_id,
+        connection,
+        |backend, name| match backend {
+            Backend::KeyValue(backend) => f(backend).map_err(CommandError::Database),
+            Backend::Sql(_) => Err(CommandError::Unsupported(format!(
+                "Connection '{}' is not a Redis connection",
+                name
+            ))),
+        },
+    )
+    .await
+}
+
+/// Drops the cached connection and credentials and connects again, e.g. after editing
+/// `.env`. Returns where the new connection points.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn reconnect(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+) -> CommandResult<ConnectionInfo> {
+    let state = Arc::clone(&state);
+    run_blocking(move || {
+        let connection = connection_name.as_deref().unwrap_or(DEFAULT_CONNECTION);
+        forget_connection(&state, &project_id, connection)?;
 
-    let backend = connections
-        .get_mut(project_id)
-        .ok_or("Failed to retrieve connection")?;
-    f(backend)
+        let opened = open_connection(&state, &project_id, connection)?;
+        let info = opened.info.clone();
+        state
+            .connections
+            .lock()
+            .map_err(|e| CommandError::Other(e.to_string()))?
+            .insert(cache_key(&project_id, connection), opened);
+        Ok(info)
+    })
+    .await
 }
 
+/// Stops the query currently running on the connection (`KILL QUERY` on MySQL, an interrupt
+/// on SQLite). The cancelled command fails with the database's "interrupted" error. Returns
+/// false when nothing was running.
 #[command(rename_all = "camelCase")]
-pub fn save_db_credentials(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state), err)]
+pub async fn cancel_query(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
-    credentials: DbCredentials,
-) -> Result<(), String> {
-    // Invalidate existing connection
-    {
-        let mut connections = state.connections.lock().map_err(|e| e.to_string())?;
-        connections.remove(&project_id);
+    connection_name: Option<String>,
+) -> CommandResult<bool> {
+    let state = Arc::clone(&state);
+    run_blocking(move || {
+        let connection = connection_name.as_deref().unwrap_or(DEFAULT_CONNECTION);
+        // Don't hold the map while the kill runs; the canceller works on its own
+        let canceller = state
+            .connections
+            .lock()
+            .map_err(|e| CommandError::Other(e.to_string()))?
+            .get(&cache_key(&project_id, connection))
+            .and_then(|opened| opened.canceller.clone());
+        match canceller {
+            Some(canceller) => canceller.cancel().map_err(CommandError::Database),
+            None => Ok(false),
+        }
+    })
+    .await
+}
+
+/// The project's connections: the primary one (from `.env` or saved settings) first, then
+/// any other saved connections by name.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn list_connections(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+) -> CommandResult<Vec<NamedConnection>> {
+    let state = Arc::clone(&state);
+    run_blocking(move || {
+        let mut connections = Vec::new();
+        match project_credentials(&state, &project_id, DEFAULT_CONNECTION) {
+            Ok(cached) => connections.push(NamedConnection {
+                name: DEFAULT_CONNECTION.to_string(),
+                info: ConnectionInfo::from(&cached.credentials),
+            }),
+            Err(CommandError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let project = find_project(&project_id)?;
+        for (name, config) in saved_connections(&project) {
+            if name != DEFAULT_CONNECTION {
+                connections.push(NamedConnection {
+                    info: ConnectionInfo::from(&config),
+                    name,
+                });
+            }
+        }
+        Ok(connections)
+    })
+    .await
+}
+
+/// Returns the caller's PK column, or detects the table's primary key when none was given.
+/// Only single-column keys can be resolved this way.
+fn resolve_pk_column(
+    backend: &mut Box<dyn DbBackend + Send>,
+    table_name: &str,
+    pk_column: Option<String>,
+) -> Result<String, String> {
+    if let Some(col) = pk_column.filter(|col| !col.trim().is_empty()) {
+        return Ok(col);
     }
 
-    // Get project location
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path)
-        .map_err(|e| format!("Failed to connect to projects database: {}", e))?;
+    let mut pk = backend.get_primary_key(table_name)?;
+    match pk.len() {
+        0 => Err(format!("Table '{}' has no primary key", table_name)),
+        1 => Ok(pk.remove(0)),
+        _ => Err(format!(
+            "Table '{}' has a composite primary key ({})",
+            table_name,
+            pk.join(", ")
+        )),
+    }
+}
 
-    let mut project = match db.get_project_by_id(&project_id) {
-        Ok(Some(project)) => project,
-        Ok(None) => return Err(format!("Project with ID '{}' not found", project_id)),
-        Err(e) => return Err(format!("Database error: {}", e)),
-    };
+/// Builds the row filter for table browsing. A raw `where_clause` is only honoured when the
+/// caller opts in with `raw: true`; otherwise the structured `filters` are used.
+fn build_row_filter(
+    filters: Option<Vec<ColumnFilter>>,
+    where_clause: Option<String>,
+    raw: Option<bool>,
+) -> CommandResult<RowFilter> {
+    match where_clause.filter(|clause| !clause.trim().is_empty()) {
+        Some(clause) if raw.unwrap_or(false) => Ok(RowFilter::Raw(clause)),
+        Some(_) => Err(CommandError::InvalidInput(
+            "Raw WHERE clauses must be enabled with `raw: true`; use structured filters instead"
+                .to_string(),
+        )),
+        None => Ok(RowFilter::Columns(filters.unwrap_or_default())),
+    }
+}
 
-    // Serialize credentials to JSON
-    let config_str = serde_json::to_string(&credentials)
-        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+/// Destructive table operations must be explicitly confirmed by the caller.
+fn require_confirmation(
+    confirm: Option<bool>,
+    action: &str,
+    table_name: &str,
+) -> CommandResult<()> {
+    if confirm == Some(true) {
+        Ok(())
+    } else {
+        Err(CommandError::InvalidInput(format!(
+            "Refusing to {} table '{}' without confirm: true",
+            action, table_name
+        )))
+    }
+}
 
-    project.db_config = Some(config_str);
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state, credentials), err)]
+pub async fn save_db_credentials(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    credentials: DbCredentials,
+) -> CommandResult<()> {
+    let state = Arc::clone(&state);
+    run_blocking(move || {
+        let connection = connection_name
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(DEFAULT_CONNECTION);
+        if connection.is_empty() {
+            return Err(CommandError::InvalidInput(
+                "Connection name must not be empty".to_string(),
+            ));
+        }
+        // Invalidate existing connection
+        forget_connection(&state, &project_id, connection)?;
 
-    db.update_project(&project.id, &project)
-        .map_err(|e| format!("Failed to update project: {}", e))?;
+        let project = find_project(&project_id)?;
 
-    Ok(())
+        // Serialize credentials to JSON, never persisting the plaintext password. A URL is split
+        // into its parts first so its password gets the same treatment.
+        let mut credentials = match credentials
+            .url
+            .as_deref()
+            .filter(|url| !url.trim().is_empty())
+        {
+            Some(url) => DbCredentials::from_url(url).map_err(CommandError::InvalidInput)?,
+            None => credentials,
+        };
+        let secret = secret_id(&project_id, connection);
+        match credentials.password.take() {
+            Some(password) => credentials.password = protect_password(&secret, &password)?,
+            None => delete_keychain_password(&secret),
+        }
+
+        let mut connections = saved_connections(&project);
+        connections.insert(connection.to_string(), credentials);
+        store_connections(&project, &connections)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_databases(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    include_system: Option<bool>,
+) -> CommandResult<Vec<String>> {
+    with_backend(&state, &project_id, connection_name, move |backend, _| {
+        match backend {
+            Backend::Sql(backend) => backend.get_databases(include_system.unwrap_or(false)),
+            Backend::KeyValue(backend) => Ok(backend.databases()),
+        }
+        .map_err(CommandError::Database)
+    })
+    .await
 }
 
 #[command]
-pub fn get_project_tables(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state), err)]
+pub async fn get_project_tables(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+) -> CommandResult<Vec<String>> {
+    // Redis has no tables; its numbered databases take their place
+    with_backend(&state, &project_id, connection_name, move |backend, _| {
+        match backend {
+            Backend::Sql(backend) => backend.get_tables(),
+            Backend::KeyValue(backend) => Ok(backend.databases()),
+        }
+        .map_err(CommandError::Database)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_foreign_keys(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
-) -> Result<Vec<String>, String> {
-    with_db_backend(&state, &project_id, |backend| backend.get_tables())
+    connection_name: Option<String>,
+    table_name: String,
+) -> CommandResult<Vec<ForeignKey>> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.get_foreign_keys(&table_name)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_indexes(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+) -> CommandResult<Vec<IndexInfo>> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.get_indexes(&table_name)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_primary_key(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+) -> CommandResult<Vec<String>> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.get_primary_key(&table_name)
+    })
+    .await
 }
 
 #[command]
-pub fn get_table_data(
-    state: State<DbConnectionManager>,
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_table_data(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
+    connection_name: Option<String>,
     table_name: String,
     page: u32,
     per_page: u32,
+    filters: Option<Vec<ColumnFilter>>,
     where_clause: Option<String>,
+    raw: Option<bool>,
     sort_column: Option<String>,
     sort_direction: Option<String>,
-) -> Result<TableData, String> {
-    with_db_backend(&state, &project_id, |backend| {
+) -> CommandResult<TableData> {
+    let per_page = validate_paging(page, per_page)?;
+    let filter = build_row_filter(filters, where_clause, raw)?;
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
         backend.get_table_data(
             &table_name,
             page,
             per_page,
-            where_clause,
+            &filter,
             sort_column,
             sort_direction,
         )
     })
+    .await
+}
+
+/// Finds rows where any text column contains `query` (case sensitivity follows the column
+/// collation on MySQL; SQLite's LIKE ignores ASCII case).
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn search_table(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    query: String,
+    page: u32,
+    per_page: u32,
+) -> CommandResult<TableData> {
+    let per_page = validate_paging(page, per_page)?;
+    if query.is_empty() {
+        return Err(CommandError::InvalidInput(
+            "Search query must not be empty".to_string(),
+        ));
+    }
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let filter = RowFilter::Search {
+            columns: backend.get_text_columns(&table_name)?,
+            query,
+        };
+        backend.get_table_data(&table_name, page, per_page, &filter, None, None)
+    })
+    .await
+}
+
+/// Cursor-based alternative to `get_table_data` for large tables.
+/// Pass the previous page's `next_cursor` as `after_pk` to continue.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_table_data_keyset(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    pk_column: Option<String>,
+    after_pk: Option<String>,
+    per_page: u32,
+) -> CommandResult<TableData> {
+    let per_page = clamp_per_page(per_page)?;
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let pk_column = resolve_pk_column(backend, &table_name, pk_column).map_err(|e| {
+            format!(
+                "Keyset pagination requires a sortable primary key column: {}",
+                e
+            )
+        })?;
+        backend.get_table_data_keyset(&table_name, &pk_column, after_pk, per_page)
+    })
+    .await
 }
 
 #[command]
-pub fn get_table_total_count(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state), err)]
+pub async fn get_table_total_count(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
+    connection_name: Option<String>,
     table_name: String,
+    filters: Option<Vec<ColumnFilter>>,
     where_clause: Option<String>,
-) -> Result<u64, String> {
-    with_db_backend(&state, &project_id, |backend| {
-        backend.get_total_rows(&table_name, where_clause)
+    raw: Option<bool>,
+) -> CommandResult<u64> {
+    let filter = build_row_filter(filters, where_clause, raw)?;
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.count_rows(&table_name, &filter)
     })
+    .await
 }
 
+/// Unfiltered row count that avoids a full `COUNT(*)` on large MySQL tables. The result is
+/// flagged `estimated` when it comes from table statistics, so the UI can show it as approximate.
 #[command(rename_all = "camelCase")]
-pub fn execute_query(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state), err)]
+pub async fn estimate_row_count(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+) -> CommandResult<RowCount> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let estimate = backend.estimate_row_count(&table_name)?;
+        if estimate.estimated && estimate.count < EXACT_COUNT_THRESHOLD {
+            return Ok(RowCount {
+                count: backend.count_rows(&table_name, &RowFilter::Columns(Vec::new()))?,
+                estimated: false,
+            });
+        }
+        Ok(estimate)
+    })
+    .await
+}
+
+/// Min, max, NULL and distinct counts (and the average for numeric columns) of a column.
+/// The distinct count is approximate on tables over a million rows.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn column_stats(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    column: String,
+) -> CommandResult<ColumnStats> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.column_stats(&table_name, &column)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state, query), err)]
+pub async fn execute_query(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
     query: String,
-) -> Result<TableData, String> {
-    with_db_backend(&state, &project_id, |backend| backend.execute_query(&query))
+    use_transaction: Option<bool>,
+) -> CommandResult<TableData> {
+    tracing::debug!(%query, "Executing query");
+    // Multi-statement scripts run inside a transaction unless the caller opts out or the
+    // script manages its own, like `run_sql_file`. A single statement needs no wrapper, and
+    // some (VACUUM, PRAGMA foreign_keys) fail or do nothing inside a transaction.
+    let use_transaction = use_transaction.unwrap_or_else(|| {
+        let statements = split_sql_statements(&query);
+        statements.len() > 1 && !statements.iter().any(|s| is_transaction_control(s))
+    });
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.execute_query(&query, use_transaction)
+    })
+    .await
 }
 
+/// Writes DDL and data for `tables` (all tables when omitted) to a `.sql` file and returns
+/// the number of rows dumped. Reports each finished table on `db-dump-progress-<project_id>`.
+/// A partially written file is removed if the dump fails.
 #[command(rename_all = "camelCase")]
-pub fn delete_row(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state, app_handle), err)]
+pub async fn dump_database(
+    state: State<'_, Arc<DbConnectionManager>>,
+    app_handle: AppHandle,
     project_id: String,
+    connection_name: Option<String>,
+    file_path: String,
+    tables: Option<Vec<String>>,
+) -> CommandResult<u64> {
+    if Path::new(&file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        != Some("sql")
+    {
+        return Err(CommandError::InvalidInput(format!(
+            "Dump file must have a .sql extension: {}",
+            file_path
+        )));
+    }
+
+    let event = format!("db-dump-progress-{}", project_id);
+    let path = file_path.clone();
+    let file = run_blocking(move || Ok(fs::File::create(path)?)).await?;
+    let mut out = BufWriter::new(file);
+    let result = with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let existing = backend.get_tables()?;
+        let tables = match tables {
+            Some(tables) => {
+                if let Some(missing) = tables.iter().find(|t| !existing.contains(t)) {
+                    return Err(format!("Table '{}' not found", missing));
+                }
+                tables
+            }
+            None => existing,
+        };
+
+        let total = tables.len();
+        let mut done = 0;
+        let rows = backend.dump_tables(&tables, &mut out, &mut |table, rows| {
+            done += 1;
+            let _ = app_handle.emit(
+                &event,
+                DumpProgress {
+                    table: table.to_string(),
+                    rows,
+                    done,
+                    total,
+                },
+            );
+        })?;
+        out.flush().map_err(|e| e.to_string())?;
+        Ok(rows)
+    })
+    .await;
+
+    // The writer was moved into the closure and is already closed here
+    if result.is_err() {
+        let _ = run_blocking(move || Ok(fs::remove_file(file_path)?)).await;
+    }
+    result
+}
+
+/// Runs a `.sql` script such as a dump or migration, stopping at the first failing statement.
+/// Wrapped in a transaction unless `use_transaction` is false; when omitted, scripts that
+/// manage their own transactions (e.g. SQLite dumps with `BEGIN`/`COMMIT`) run unwrapped.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn run_sql_file(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    file_path: String,
+    use_transaction: Option<bool>,
+) -> CommandResult<SqlScriptResult> {
+    let sql = fs::read_to_string(&file_path)?;
+    let statements = split_sql_script(&sql);
+    if statements.is_empty() {
+        return Err(CommandError::InvalidInput(format!(
+            "No SQL statements found in {}",
+            file_path
+        )));
+    }
+    let use_transaction =
+        use_transaction.unwrap_or_else(|| !statements.iter().any(|s| is_transaction_control(s)));
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.run_script(&statements, use_transaction)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn create_table(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    columns: Vec<ColumnDefinition>,
+) -> CommandResult<()> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.create_table(&table_name, &columns)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn drop_table(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
     table_name: String,
-    pk_column: String,
+    confirm: Option<bool>,
+) -> CommandResult<()> {
+    require_confirmation(confirm, "drop", &table_name)?;
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.drop_table(&table_name)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn truncate_table(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    confirm: Option<bool>,
+) -> CommandResult<u64> {
+    require_confirmation(confirm, "truncate", &table_name)?;
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.truncate_table(&table_name)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_row(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    pk_column: Option<String>,
     pk_value: String,
-) -> Result<u64, String> {
-    with_db_backend(&state, &project_id, |backend| {
-        backend.delete_row(&table_name, &pk_column, &pk_value)
+) -> CommandResult<u64> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let pk_column = resolve_pk_column(backend, &table_name, pk_column)?;
+        backend.delete_row(&table_name, &[(pk_column, pk_value)])
     })
+    .await
 }
 
+/// Returns the row as an `INSERT` statement for copying it into another database.
 #[command(rename_all = "camelCase")]
-pub fn update_row(
-    state: State<DbConnectionManager>,
+#[tracing::instrument(skip(state), err)]
+pub async fn row_as_insert(
+    state: State<'_, Arc<DbConnectionManager>>,
     project_id: String,
+    connection_name: Option<String>,
     table_name: String,
-    pk_column: String,
+    pk_column: Option<String>,
+    pk_value: String,
+) -> CommandResult<String> {
+    let (table, key) = (table_name.clone(), pk_value.clone());
+    let statement = with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let pk_column = resolve_pk_column(backend, &table, pk_column)?;
+        backend.row_as_insert(&table, &[(pk_column, key)])
+    })
+    .await?;
+    statement.ok_or_else(|| {
+        CommandError::NotFound(format!(
+            "No row in '{}' with key '{}'",
+            table_name, pk_value
+        ))
+    })
+}
+
+/// Composite-key variant of `delete_row`; `pk` is a list of `[column, value]` pairs.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_row_by_key(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    pk: Vec<(String, String)>,
+) -> CommandResult<u64> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.delete_row(&table_name, &pk)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(state, data, expected), err)]
+pub async fn update_row(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    pk_column: Option<String>,
     pk_value: String,
     data: HashMap<String, Option<String>>,
-) -> Result<u64, String> {
-    with_db_backend(&state, &project_id, |backend| {
-        backend.update_row(&table_name, &pk_column, &pk_value, data)
+    expected: Option<HashMap<String, Option<String>>>,
+) -> CommandResult<u64> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        let pk_column = resolve_pk_column(backend, &table_name, pk_column)?;
+        backend.update_row(
+            &table_name,
+            &[(pk_column, pk_value)],
+            expected.as_ref(),
+            data,
+        )
     })
+    .await
 }
 
+/// Composite-key variant of `update_row`; `pk` is a list of `[column, value]` pairs.
 #[command(rename_all = "camelCase")]
-pub fn get_db_connection_type(project_id: String) -> Result<String, String> {
-    // Implementation remains mostly same, just reading config
-    // ... Copy existing implementation ...
-    // Get project location
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path)
-        .map_err(|e| format!("Failed to connect to projects database: {}", e))?;
-
-    let project = match db.get_project_by_id(&project_id) {
-        Ok(Some(project)) => project,
-        Ok(None) => {
-            return Err(format!(
-                "Project with ID '{}' not found in database",
-                project_id
-            ))
-        }
-        Err(e) => return Err(format!("Database error while fetching project: {}", e)),
-    };
+#[tracing::instrument(skip(state, data, expected), err)]
+pub async fn update_row_by_key(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    table_name: String,
+    pk: Vec<(String, String)>,
+    data: HashMap<String, Option<String>>,
+    expected: Option<HashMap<String, Option<String>>>,
+) -> CommandResult<u64> {
+    with_db_backend(&state, &project_id, connection_name, move |backend| {
+        backend.update_row(&table_name, &pk, expected.as_ref(), data)
+    })
+    .await
+}
 
-    // 1. Try .env file first (for Laravel or other dotenv projects)
-    let env_path = Path::new(&project.location).join(".env");
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_db_connection_type(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+) -> CommandResult<String> {
+    let state = Arc::clone(&state);
+    run_blocking(move || {
+        let connection = connection_name.as_deref().unwrap_or(DEFAULT_CONNECTION);
+        Ok(project_credentials(&state, &project_id, connection)?
+            .credentials
+            .connection)
+    })
+    .await
+}
 
-    if env_path.exists() {
-        if let Ok(env_content) = fs::read_to_string(&env_path) {
-            let mut env_vars = HashMap::new();
-            for line in env_content.lines() {
-                if line.trim().is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some((key, value)) = line.split_once('=') {
-                    let val = value.trim();
-                    let val = if val.starts_with('"') && val.ends_with('"') {
-                        &val[1..val.len() - 1]
-                    } else {
-                        val
-                    };
-                    env_vars.insert(key.trim().to_string(), val.to_string());
-                }
-            }
+/// Keys of a Redis connection matching a glob-style `pattern` (`*` when empty). `database`
+/// picks one of the numbered databases and defaults to the connection's own.
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn redis_keys(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    database: Option<i64>,
+    pattern: String,
+) -> CommandResult<RedisKeys> {
+    with_key_value_backend(&state, &project_id, connection_name, move |redis| {
+        redis.keys(database, &pattern)
+    })
+    .await
+}
 
-            if let Some(conn) = env_vars.get("DB_CONNECTION") {
-                return Ok(conn.clone());
-            }
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn redis_get(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    database: Option<i64>,
+    key: String,
+) -> CommandResult<RedisValue> {
+    with_key_value_backend(&state, &project_id, connection_name, move |redis| {
+        redis.get(database, &key)
+    })
+    .await
+}
+
+#[command(rename_all = "camelCase")]
+#[tracing::instrument(skip(state), err)]
+pub async fn redis_type(
+    state: State<'_, Arc<DbConnectionManager>>,
+    project_id: String,
+    connection_name: Option<String>,
+    database: Option<i64>,
+    key: String,
+) -> CommandResult<String> {
+    with_key_value_backend(&state, &project_id, connection_name, move |redis| {
+        redis.key_type(database, &key)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MYSQL_ENV: &str = "DB_CONNECTION=mysql\nDB_HOST=127.0.0.1\nDB_PORT=3306\nDB_DATABASE=shop\nDB_USERNAME=root\nDB_PASSWORD=secret\n";
+    const PROJECT_JSON: &str = r#"{"database": {"connection": "mysql", "host": "db.local", "port": "3307", "database": "legacy", "username": "app", "password": "pw"}}"#;
+
+    fn project_dir(env: Option<&str>, project_json: Option<&str>) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        if let Some(env) = env {
+            fs::write(dir.path().join(".env"), env).unwrap();
+        }
+        if let Some(json) = project_json {
+            fs::create_dir(dir.path().join(".workshop")).unwrap();
+            fs::write(dir.path().join(".workshop/project.json"), json).unwrap();
         }
+        dir
     }
 
-    // 2. If not found in .env, try project.db_config (internal DB)
-    if let Some(config_str) = &project.db_config {
-        if let Ok(config) = serde_json::from_str::<DbCredentials>(config_str) {
-            return Ok(config.connection);
-        }
+    fn saved() -> Option<DbCredentials> {
+        Some(DbCredentials {
+            connection: "mysql".to_string(),
+            database: "saved".to_string(),
+            ..Default::default()
+        })
     }
 
-    // 3. Fallback: Check .workshop/project.json
-    let project_json_path = Path::new(&project.location)
-        .join(".workshop")
-        .join("project.json");
-    if project_json_path.exists() {
-        if let Ok(content) = fs::read_to_string(&project_json_path) {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(db_config) = json.get("database") {
-                    if let Some(connection) = db_config["connection"].as_str() {
-                        return Ok(connection.to_string());
-                    }
-                }
-            }
-        }
+    #[test]
+    fn env_wins_over_saved_and_project_json() {
+        let dir = project_dir(Some(MYSQL_ENV), Some(PROJECT_JSON));
+        let Some(DefaultCredentials::Env(creds)) =
+            find_default_credentials(dir.path(), saved()).unwrap()
+        else {
+            panic!("expected credentials from .env");
+        };
+        assert_eq!(creds.connection, "mysql");
+        assert_eq!(creds.host.as_deref(), Some("127.0.0.1"));
+        assert_eq!(creds.database, "shop");
+        assert_eq!(creds.password.as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn env_sqlite_and_url_are_read() {
+        let dir = project_dir(
+            Some("DB_CONNECTION=sqlite\nDB_DATABASE=database/app.sqlite\n"),
+            None,
+        );
+        let Some(DefaultCredentials::Env(creds)) =
+            find_default_credentials(dir.path(), None).unwrap()
+        else {
+            panic!("expected credentials from .env");
+        };
+        assert_eq!(creds.connection, "sqlite");
+        assert_eq!(creds.database, "database/app.sqlite");
+
+        let dir = project_dir(Some("DATABASE_URL=mysql://app:pw@db:3306/shop\n"), None);
+        let Some(DefaultCredentials::Env(creds)) =
+            find_default_credentials(dir.path(), None).unwrap()
+        else {
+            panic!("expected credentials from .env");
+        };
+        assert_eq!(creds.host.as_deref(), Some("db"));
+        assert_eq!(creds.username.as_deref(), Some("app"));
+        assert_eq!(creds.database, "shop");
+    }
+
+    #[test]
+    fn saved_config_is_used_when_env_is_incomplete() {
+        // No DB_PASSWORD, so the .env doesn't describe a full MySQL connection
+        let env = MYSQL_ENV.replace("DB_PASSWORD=secret\n", "");
+        let dir = project_dir(Some(&env), Some(PROJECT_JSON));
+        let Some(DefaultCredentials::Saved(creds)) =
+            find_default_credentials(dir.path(), saved()).unwrap()
+        else {
+            panic!("expected the saved credentials");
+        };
+        assert_eq!(creds.database, "saved");
+    }
+
+    #[test]
+    fn project_json_is_the_last_resort() {
+        let dir = project_dir(None, Some(PROJECT_JSON));
+        let Some(DefaultCredentials::ProjectJson(creds)) =
+            find_default_credentials(dir.path(), None).unwrap()
+        else {
+            panic!("expected credentials from project.json");
+        };
+        assert_eq!(creds.host.as_deref(), Some("db.local"));
+        assert_eq!(creds.port.as_deref(), Some("3307"));
+        assert_eq!(creds.database, "legacy");
     }
 
-    Err("Database configuration not found".to_string())
+    #[test]
+    fn nothing_configured_finds_nothing() {
+        let dir = project_dir(Some("APP_NAME=Shop\n"), None);
+        assert!(find_default_credentials(dir.path(), None)
+            .unwrap()
+            .is_none());
+
+        // The legacy file only ever held MySQL connections
+        let dir = project_dir(None, Some(r#"{"database": {"connection": "pgsql"}}"#));
+        assert!(find_default_credentials(dir.path(), None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn invalid_database_url_is_an_error() {
+        let dir = project_dir(Some("DATABASE_URL=not a url\n"), None);
+        assert!(matches!(
+            find_default_credentials(dir.path(), saved()),
+            Err(CommandError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn per_page_is_clamped_to_the_maximum() {
+        assert!(matches!(
+            clamp_per_page(0),
+            Err(CommandError::InvalidInput(_))
+        ));
+        assert_eq!(clamp_per_page(1).unwrap(), 1);
+        assert_eq!(clamp_per_page(MAX_PER_PAGE).unwrap(), MAX_PER_PAGE);
+        assert_eq!(clamp_per_page(MAX_PER_PAGE + 1).unwrap(), MAX_PER_PAGE);
+        assert_eq!(clamp_per_page(u32::MAX).unwrap(), MAX_PER_PAGE);
+    }
+
+    #[test]
+    fn paging_rejects_page_zero_and_accepts_huge_pages() {
+        assert!(matches!(
+            validate_paging(0, 50),
+            Err(CommandError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            validate_paging(1, 0),
+            Err(CommandError::InvalidInput(_))
+        ));
+        assert_eq!(validate_paging(1, 1).unwrap(), 1);
+        assert_eq!(validate_paging(2, MAX_PER_PAGE + 1).unwrap(), MAX_PER_PAGE);
+        // The offset (page - 1) * per_page would overflow u32; backends compute it in u64
+        assert_eq!(validate_paging(u32::MAX, u32::MAX).unwrap(), MAX_PER_PAGE);
+    }
 }
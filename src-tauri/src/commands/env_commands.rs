@@ -0,0 +1,195 @@
+use crate::commands::project_commands::configure_command_env;
+use crate::utils::{parse_env, parse_env_entries, project_location};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::command;
+
+#[derive(Debug, Serialize)]
+pub struct EnvValueChange {
+    pub key: String,
+    pub example: String,
+    pub value: String,
+}
+
+/// How `.env` differs from `.env.example`. Keys are sorted.
+#[derive(Debug, Serialize)]
+pub struct EnvDiff {
+    /// In `.env.example` but missing from `.env`
+    pub added: Vec<String>,
+    /// Only in `.env`
+    pub removed: Vec<String>,
+    /// In both, with a different value
+    pub changed: Vec<EnvValueChange>,
+}
+
+/// Variables defined in the project's `.env`. Empty if there is no `.env`.
+#[command]
+pub fn get_env_vars(project_id: String) -> Result<HashMap<String, String>, String> {
+    let env_path = project_location(&project_id)?.join(".env");
+    if !env_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = std::fs::read_to_string(&env_path).map_err(|e| e.to_string())?;
+    Ok(parse_env(&content))
+}
+
+/// Sets one variable in the project's `.env`, leaving every other line as it was. The file is
+/// created from `.env.example` (or empty) if it doesn't exist yet.
+#[command]
+pub fn set_env_var(project_id: String, key: String, value: String) -> Result<(), String> {
+    let valid_key = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        return Err(format!("Invalid environment variable name: {}", key));
+    }
+
+    let location = project_location(&project_id)?;
+    let env_path = location.join(".env");
+    let content = if env_path.exists() {
+        std::fs::read_to_string(&env_path).map_err(|e| e.to_string())?
+    } else {
+        let example_path = location.join(".env.example");
+        if example_path.exists() {
+            std::fs::read_to_string(&example_path).map_err(|e| e.to_string())?
+        } else {
+            String::new()
+        }
+    };
+
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let formatted = format_env_value(&value);
+
+    // Later definitions win when a key is repeated, so update the last one
+    match parse_env_entries(&content)
+        .into_iter()
+        .rev()
+        .find(|entry| entry.key == key)
+    {
+        Some(entry) => {
+            let prefix = if lines[entry.lines.start].trim_start().starts_with("export ") {
+                "export "
+            } else {
+                ""
+            };
+            lines.splice(entry.lines, [format!("{}{}={}", prefix, key, formatted)]);
+        }
+        None => lines.push(format!("{}={}", key, formatted)),
+    }
+
+    let mut updated = lines.join(newline);
+    updated.push_str(newline);
+    std::fs::write(&env_path, updated).map_err(|e| e.to_string())
+}
+
+/// Generates a new `APP_KEY` and saves it to the project's `.env`, replacing the existing
+/// line if there is one. Returns the key.
+#[command(async)]
+pub fn generate_app_key(project_id: String) -> Result<String, String> {
+    let location = project_location(&project_id)?;
+    let key = artisan_app_key(&location).unwrap_or_else(|| {
+        // Same format as key:generate: 32 random bytes for the default AES-256-CBC cipher
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        format!("base64:{}", BASE64.encode(bytes))
+    });
+    set_env_var(project_id, "APP_KEY".to_string(), key.clone())?;
+    Ok(key)
+}
+
+/// Asks artisan for a key without letting it write `.env`, which fails when `.env` has no
+/// `APP_KEY` line. None when the project has no artisan or PHP isn't available.
+fn artisan_app_key(location: &Path) -> Option<String> {
+    if !location.join("artisan").exists() {
+        return None;
+    }
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(location)
+        .args(["artisan", "key:generate", "--show", "--no-ansi"]);
+    configure_command_env(&mut cmd);
+    let output = cmd.output().ok().filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("base64:"))
+        .map(str::to_string)
+}
+
+/// Compares the project's `.env` with `.env.example`, e.g. to spot variables added upstream.
+#[command]
+pub fn diff_env(project_id: String) -> Result<EnvDiff, String> {
+    let location = project_location(&project_id)?;
+    let example_path = location.join(".env.example");
+    if !example_path.exists() {
+        return Err("This project has no .env.example to compare against".to_string());
+    }
+    let example = parse_env(&std::fs::read_to_string(&example_path).map_err(|e| e.to_string())?);
+
+    let env_path = location.join(".env");
+    let env = if env_path.exists() {
+        parse_env(&std::fs::read_to_string(&env_path).map_err(|e| e.to_string())?)
+    } else {
+        HashMap::new()
+    };
+
+    let mut added: Vec<String> = example
+        .keys()
+        .filter(|key| !env.contains_key(*key))
+        .cloned()
+        .collect();
+    let mut removed: Vec<String> = env
+        .keys()
+        .filter(|key| !example.contains_key(*key))
+        .cloned()
+        .collect();
+    let mut changed: Vec<EnvValueChange> = example
+        .iter()
+        .filter_map(|(key, example)| {
+            let value = env.get(key)?;
+            (value != example).then(|| EnvValueChange {
+                key: key.clone(),
+                example: example.clone(),
+                value: value.clone(),
+            })
+        })
+        .collect();
+
+    added.sort();
+    removed.sort();
+    changed.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(EnvDiff {
+        added,
+        removed,
+        changed,
+    })
+}
+
+/// Quotes a value only when it would otherwise be misread. Single quotes keep `$` from being
+/// interpolated by phpdotenv; double quotes are used when the value needs escapes.
+fn format_env_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| !c.is_whitespace() && !matches!(c, '#' | '"' | '\'' | '\\' | '$' | '='));
+    if plain {
+        return value.to_string();
+    }
+    if value.contains('$') && !value.contains('\'') && !value.contains('\n') {
+        return format!("'{}'", value);
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
@@ -0,0 +1,197 @@
+use crate::commands::project_commands::configure_command_env;
+use crate::utils::{project_location, read_git_branch};
+use serde::Serialize;
+use std::path::Path;
+use tauri::command;
+
+#[derive(Debug, Serialize)]
+pub struct GitFileChange {
+    pub path: String,
+    /// Single-letter git status code: M, A, D, R, C, T or U
+    pub status: String,
+    /// Previous path of a renamed or copied file
+    pub orig_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct GitStatus {
+    /// None when HEAD is detached
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub staged: Vec<GitFileChange>,
+    pub unstaged: Vec<GitFileChange>,
+    pub untracked: Vec<String>,
+    /// Files with unresolved merge conflicts
+    pub conflicted: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author: String,
+    /// Author date, ISO 8601
+    pub date: String,
+    pub subject: String,
+}
+
+/// Runs `git <args>` in `location`. Returns `None` when the folder isn't a git repository.
+fn git_output(location: &Path, args: &[&str]) -> Result<Option<String>, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.current_dir(location).args(args);
+    configure_command_env(&mut cmd);
+
+    let output = cmd.output().map_err(|e| {
+        format!(
+            "Failed to execute git: {}. Make sure git is installed and in your PATH.",
+            e
+        )
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not a git repository") {
+            return Ok(None);
+        }
+        return Err(stderr.trim().to_string());
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+/// Checked-out branch of the project's repository; `None` for a detached HEAD or a folder
+/// that isn't a git repository.
+#[command]
+pub fn get_git_branch(project_id: String) -> Result<Option<String>, String> {
+    let location = project_location(&project_id)?;
+    Ok(read_git_branch(&location))
+}
+
+/// Branch, upstream tracking and changed files of the project's repository, or `None` if the
+/// project isn't a git repository.
+#[command]
+pub fn git_status(project_id: String) -> Result<Option<GitStatus>, String> {
+    let location = project_location(&project_id)?;
+    // -z keeps paths unquoted and NUL-separated
+    let Some(output) = git_output(&location, &["status", "--porcelain=v2", "--branch", "-z"])?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(parse_porcelain_v2(&output)))
+}
+
+fn parse_porcelain_v2(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|r| !r.is_empty());
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (name, value) = header.split_once(' ').unwrap_or((header, ""));
+            match name {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // Ordinary ("1"), renamed/copied ("2") and unmerged ("u") entries have a fixed number
+        // of space-separated fields before the path, which may itself contain spaces
+        let (kind, rest) = record.split_at(1);
+        let rest = rest.trim_start();
+        match kind {
+            "?" => status.untracked.push(rest.to_string()),
+            "1" | "2" | "u" => {
+                let fields_before_path = match kind {
+                    "1" => 7,
+                    "2" => 8,
+                    _ => 9,
+                };
+                let mut fields = rest.splitn(fields_before_path + 1, ' ');
+                let xy = fields.next().unwrap_or("..");
+                let Some(path) = fields.nth(fields_before_path - 1) else {
+                    continue;
+                };
+                if kind == "u" {
+                    status.conflicted.push(path.to_string());
+                    continue;
+                }
+                // The original path of a rename follows as its own record
+                let orig_path = if kind == "2" {
+                    records.next().map(str::to_string)
+                } else {
+                    None
+                };
+
+                let mut codes = xy.chars();
+                let (index, worktree) = (codes.next(), codes.next());
+                if let Some(code) = index.filter(|c| *c != '.') {
+                    status.staged.push(GitFileChange {
+                        path: path.to_string(),
+                        status: code.to_string(),
+                        orig_path: orig_path.clone(),
+                    });
+                }
+                if let Some(code) = worktree.filter(|c| *c != '.') {
+                    status.unstaged.push(GitFileChange {
+                        path: path.to_string(),
+                        status: code.to_string(),
+                        orig_path,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    status
+}
+
+/// The most recent commits on the current branch, newest first. Empty for a repository
+/// without commits or a folder that isn't a repository.
+#[command]
+pub fn git_log(project_id: String, limit: Option<u32>) -> Result<Vec<GitCommit>, String> {
+    let location = project_location(&project_id)?;
+
+    // Fields are split on the unit separator (0x1f), which can't appear in a commit subject
+    let limit = format!("-n{}", limit.unwrap_or(20).max(1));
+    let args = [
+        "log",
+        limit.as_str(),
+        "--date=iso-strict",
+        "--pretty=format:%H%x1f%an%x1f%ad%x1f%s",
+    ];
+    let output = match git_output(&location, &args) {
+        Ok(Some(output)) => output,
+        Ok(None) => return Ok(Vec::new()),
+        // A fresh repository has no HEAD to log from yet (older git says "bad default revision")
+        Err(e)
+            if e.contains("does not have any commits yet")
+                || e.contains("bad default revision") =>
+        {
+            return Ok(Vec::new())
+        }
+        Err(e) => return Err(e),
+    };
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            Some(GitCommit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
@@ -1,8 +1,8 @@
-use crate::database::Database;
-use crate::utils::get_db_path;
-use std::collections::HashMap;
+use crate::utils::{parse_env, project_location};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use tauri::command;
 
 #[derive(serde::Serialize)]
@@ -24,16 +24,38 @@ pub struct LangData {
     locales: Vec<LangLocale>,
 }
 
+#[derive(serde::Serialize)]
+pub struct PlaceholderMismatch {
+    key: String,
+    locale: String,
+    expected: Vec<String>,
+    found: Vec<String>,
+}
+
+/// The project's translations folder: `lang/` (Laravel 9+) or `resources/lang/` for older
+/// projects. New projects get `lang/`.
+fn lang_root(project_path: &Path) -> PathBuf {
+    let resources_lang_path = project_path.join("resources/lang");
+    if !project_path.join("lang").exists() && resources_lang_path.exists() {
+        return resources_lang_path;
+    }
+    project_path.join("lang")
+}
+
+fn valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale != "vendor"
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
 fn get_default_locale(project_path: &Path) -> String {
     let env_path = project_path.join(".env");
     if env_path.exists() {
         if let Ok(content) = fs::read_to_string(&env_path) {
-            for line in content.lines() {
-                let line = line.trim();
-                if line.starts_with("APP_LOCALE=") {
-                    let value = line.strip_prefix("APP_LOCALE=").unwrap_or("en");
-                    return value.trim_matches('"').trim_matches('\'').to_lowercase();
-                }
+            if let Some(locale) = parse_env(&content).get("APP_LOCALE") {
+                return locale.to_lowercase();
             }
         }
     }
@@ -131,29 +153,22 @@ fn collect_files_in_locale(
 
 #[command]
 pub fn get_lang_files(id: String) -> Result<LangData, String> {
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-
-    let project_path = Path::new(&project.location);
+    let project_path = project_location(&id)?;
     let lang_path = project_path.join("lang");
     let resources_lang_path = project_path.join("resources/lang");
 
-    let default_locale = get_default_locale(project_path);
+    let default_locale = get_default_locale(&project_path);
     let mut locale_map: HashMap<String, Vec<LangFile>> = HashMap::new();
 
     if lang_path.exists() {
-        let _ = collect_lang_files(&lang_path, &mut locale_map, project_path, &lang_path);
+        let _ = collect_lang_files(&lang_path, &mut locale_map, &project_path, &lang_path);
     }
 
     if resources_lang_path.exists() {
         let _ = collect_lang_files(
             &resources_lang_path,
             &mut locale_map,
-            project_path,
+            &project_path,
             &resources_lang_path,
         );
     }
@@ -182,36 +197,1322 @@ pub fn get_lang_files(id: String) -> Result<LangData, String> {
 
 #[command]
 pub fn read_lang_file(id: String, file_path: String) -> Result<String, String> {
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-
     if file_path.contains("..") {
         return Err("Invalid file path".to_string());
     }
 
-    let full_path = Path::new(&project.location).join(&file_path);
+    let full_path = project_location(&id)?.join(&file_path);
 
     fs::read_to_string(full_path).map_err(|e| e.to_string())
 }
 
 #[command]
 pub fn save_lang_file(id: String, file_path: String, content: String) -> Result<(), String> {
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-
     if file_path.contains("..") {
         return Err("Invalid file path".to_string());
     }
 
-    let full_path = Path::new(&project.location).join(&file_path);
+    let full_path = project_location(&id)?.join(&file_path);
 
     fs::write(full_path, content).map_err(|e| e.to_string())
 }
+
+/// Adds a locale to the project. With `copy_from`, the files of that locale are copied so
+/// translators start from its strings; otherwise an empty locale is created in whichever
+/// layout (a `<locale>/` folder or a `<locale>.json` file) the project already uses.
+/// Returns the created files relative to the project.
+#[command]
+pub fn create_locale(
+    id: String,
+    locale: String,
+    copy_from: Option<String>,
+) -> Result<Vec<String>, String> {
+    if !valid_locale(&locale) {
+        return Err("Invalid locale".to_string());
+    }
+    let project_path = project_location(&id)?;
+    let lang_path = lang_root(&project_path);
+
+    let locale_dir = lang_path.join(&locale);
+    let locale_json = lang_path.join(format!("{}.json", locale));
+    if locale_dir.exists() || locale_json.exists() {
+        return Err(format!("Locale '{}' already exists", locale));
+    }
+
+    let mut created = Vec::new();
+    match copy_from {
+        Some(source) => {
+            if !valid_locale(&source) {
+                return Err("Invalid locale".to_string());
+            }
+            let source_dir = lang_path.join(&source);
+            let source_json = lang_path.join(format!("{}.json", source));
+            if !source_dir.is_dir() && !source_json.is_file() {
+                return Err(format!("Locale '{}' not found", source));
+            }
+            if source_dir.is_dir() {
+                copy_locale_dir(&source_dir, &locale_dir, &mut created)
+                    .map_err(|e| e.to_string())?;
+            }
+            if source_json.is_file() {
+                fs::copy(&source_json, &locale_json).map_err(|e| e.to_string())?;
+                created.push(locale_json);
+            }
+        }
+        None => {
+            let uses_json_only = fs::read_dir(&lang_path)
+                .map(|entries| {
+                    let paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+                    let has_locale_dirs = paths
+                        .iter()
+                        .any(|p| p.is_dir() && p.file_name().is_some_and(|name| name != "vendor"));
+                    let has_json = paths
+                        .iter()
+                        .any(|p| p.extension().is_some_and(|ext| ext == "json"));
+                    has_json && !has_locale_dirs
+                })
+                .unwrap_or(false);
+
+            if uses_json_only {
+                fs::write(&locale_json, "{}\n").map_err(|e| e.to_string())?;
+                created.push(locale_json);
+            } else {
+                fs::create_dir_all(&locale_dir).map_err(|e| e.to_string())?;
+                created.push(locale_dir);
+            }
+        }
+    }
+
+    Ok(created
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&project_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect())
+}
+
+/// Copies the `.php` and `.json` files of a locale folder, keeping its subfolders.
+fn copy_locale_dir(
+    source: &Path,
+    target: &Path,
+    created: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target_path = target.join(entry.file_name());
+
+        if path.is_dir() {
+            copy_locale_dir(&path, &target_path, created)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "php" || ext == "json")
+        {
+            fs::copy(&path, &target_path)?;
+            created.push(target_path);
+        }
+    }
+    Ok(())
+}
+
+/// Sets one translation, creating the file if needed. `file` names a PHP group file such as
+/// `auth` or `auth.php` in the locale folder; without it the key goes in `<locale>.json`.
+#[command]
+pub fn set_translation(
+    id: String,
+    locale: String,
+    file: Option<String>,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Translation key is empty".to_string());
+    }
+    let project_path = project_location(&id)?;
+    let path = translation_path(&lang_root(&project_path), &locale, file.as_deref())?;
+
+    if file.is_some() {
+        // Patch the existing file so comments, formatting and key order survive
+        let content = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            patch_php_lang(&content, &key, &value)
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+        } else {
+            render_php_lang(&[(key, value)])?
+        };
+        write_lang_file(&path, &content)
+    } else {
+        let mut entries = read_json_translations(&path)?;
+        entries.insert(key, serde_json::Value::String(value));
+        write_json_translations(&path, &entries)
+    }
+}
+
+/// Removes a translation. For PHP files, a key naming a nested array removes the whole
+/// array, and arrays left empty are dropped. Returns whether anything was removed.
+#[command]
+pub fn delete_translation(
+    id: String,
+    locale: String,
+    file: Option<String>,
+    key: String,
+) -> Result<bool, String> {
+    let project_path = project_location(&id)?;
+    let path = translation_path(&lang_root(&project_path), &locale, file.as_deref())?;
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    if file.is_some() {
+        let mut entries = read_php_translations(&path)?;
+        let before = entries.len();
+        let prefix = format!("{}.", key);
+        // Nested arrays only exist through their leaves, so empty parents disappear too
+        entries.retain(|(k, _)| *k != key && !k.starts_with(&prefix));
+        if entries.len() == before {
+            return Ok(false);
+        }
+        write_php_translations(&path, &entries)?;
+    } else {
+        let mut entries = read_json_translations(&path)?;
+        if entries.remove(&key).is_none() {
+            return Ok(false);
+        }
+        write_json_translations(&path, &entries)?;
+    }
+    Ok(true)
+}
+
+/// Finds translations whose `:placeholder`s differ from the default locale's version of the
+/// same key. Keys missing from a locale aren't reported here.
+#[command]
+pub fn validate_placeholders(id: String) -> Result<Vec<PlaceholderMismatch>, String> {
+    let project_path = project_location(&id)?;
+    let lang_path = lang_root(&project_path);
+    let default_locale = get_default_locale(&project_path);
+    let default_translations = load_locale_translations(&lang_path, &default_locale)?;
+
+    let mut mismatches = Vec::new();
+    for locale in project_locales(&lang_path) {
+        if locale == default_locale {
+            continue;
+        }
+        let translations = load_locale_translations(&lang_path, &locale)?;
+        for (key, default_value) in &default_translations {
+            let Some(value) = translations.get(key) else {
+                continue;
+            };
+            let expected = placeholders(default_value);
+            let found = placeholders(value);
+            if expected != found {
+                mismatches.push(PlaceholderMismatch {
+                    key: key.clone(),
+                    locale: locale.clone(),
+                    expected: expected.into_iter().collect(),
+                    found: found.into_iter().collect(),
+                });
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Writes every translation to a CSV file at `file_path`, with a `key` column followed by
+/// one column per locale (default locale first). Missing translations are left empty.
+/// Returns the number of keys exported.
+#[command]
+pub fn export_translations_csv(id: String, file_path: String) -> Result<usize, String> {
+    let project_path = project_location(&id)?;
+    let lang_path = lang_root(&project_path);
+    let default_locale = get_default_locale(&project_path);
+
+    let mut locales = project_locales(&lang_path);
+    if let Some(index) = locales.iter().position(|l| *l == default_locale) {
+        let default = locales.remove(index);
+        locales.insert(0, default);
+    }
+
+    let mut by_locale = Vec::new();
+    let mut keys = BTreeSet::new();
+    for locale in &locales {
+        let translations = load_locale_translations(&lang_path, locale)?;
+        keys.extend(translations.keys().cloned());
+        by_locale.push(translations);
+    }
+
+    // Start with a BOM so spreadsheet apps detect UTF-8
+    let mut csv = String::from("\u{feff}");
+    let header: Vec<&str> = std::iter::once("key")
+        .chain(locales.iter().map(String::as_str))
+        .collect();
+    push_csv_row(&mut csv, &header);
+    for key in &keys {
+        let row: Vec<&str> = std::iter::once(key.as_str())
+            .chain(
+                by_locale
+                    .iter()
+                    .map(|translations| translations.get(key).map_or("", String::as_str)),
+            )
+            .collect();
+        push_csv_row(&mut csv, &row);
+    }
+
+    fs::write(&file_path, csv).map_err(|e| e.to_string())?;
+    Ok(keys.len())
+}
+
+fn push_csv_row(csv: &mut String, fields: &[&str]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) || field.trim() != *field {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    csv.push_str(&fields.join(","));
+    csv.push_str("\r\n");
+}
+
+/// Reads a CSV in the `export_translations_csv` layout and writes every non-empty cell into
+/// the matching lang file. Keys starting with a PHP group name (`auth.failed`) go to that
+/// group file; all others to the locale's JSON file. Locales not in the project yet are
+/// created. Returns the number of keys added or changed per locale.
+#[command]
+pub fn import_translations_csv(
+    id: String,
+    file_path: String,
+) -> Result<BTreeMap<String, usize>, String> {
+    let project_path = project_location(&id)?;
+    let lang_path = lang_root(&project_path);
+
+    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+    let rows = parse_csv(content.trim_start_matches('\u{feff}'))?;
+    let Some((header, rows)) = rows.split_first() else {
+        return Err("The CSV file is empty".to_string());
+    };
+    if header.first().map(|h| h.trim().to_lowercase()).as_deref() != Some("key") {
+        return Err("The first column must be `key`".to_string());
+    }
+    let locales: Vec<String> = header[1..].iter().map(|l| l.trim().to_string()).collect();
+    for (i, locale) in locales.iter().enumerate() {
+        if !valid_locale(locale) {
+            return Err(format!("Invalid locale '{}' in the header", locale));
+        }
+        if locales[..i].contains(locale) {
+            return Err(format!("Locale '{}' appears twice in the header", locale));
+        }
+    }
+
+    // Group files from every locale, longest first so `admin/users` wins over `admin`
+    let mut groups = BTreeSet::new();
+    for locale in project_locales(&lang_path) {
+        let locale_dir = lang_path.join(&locale);
+        if locale_dir.is_dir() {
+            collect_php_groups(&locale_dir, &locale_dir, &mut groups).map_err(|e| e.to_string())?;
+        }
+    }
+    let mut groups: Vec<String> = groups.into_iter().collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+
+    let mut updated = BTreeMap::new();
+    for (column, locale) in locales.iter().enumerate() {
+        let mut json: Option<serde_json::Map<String, serde_json::Value>> = None;
+        let mut php: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        let mut changed = 0;
+
+        for row in rows {
+            let key = row.first().map(|k| k.trim()).unwrap_or("");
+            let value = row.get(column + 1).map(String::as_str).unwrap_or("");
+            if key.is_empty() || value.is_empty() {
+                continue;
+            }
+
+            let group = groups.iter().find_map(|group| {
+                key.strip_prefix(group.as_str())
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .filter(|rest| !rest.is_empty())
+                    .map(|rest| (group, rest))
+            });
+            let previous = match group {
+                Some((group, group_key)) => {
+                    if !php.contains_key(group) {
+                        let path = translation_path(&lang_path, locale, Some(group))?;
+                        php.insert(group.clone(), read_php_translations(&path)?);
+                    }
+                    php.get_mut(group).and_then(|entries| {
+                        set_entry(entries, group_key.to_string(), value.to_string())
+                    })
+                }
+                None => {
+                    if json.is_none() {
+                        let path = translation_path(&lang_path, locale, None)?;
+                        json = Some(read_json_translations(&path)?);
+                    }
+                    json.as_mut()
+                        .and_then(|entries| {
+                            entries.insert(
+                                key.to_string(),
+                                serde_json::Value::String(value.to_string()),
+                            )
+                        })
+                        .and_then(|previous| previous.as_str().map(str::to_string))
+                }
+            };
+            if previous.as_deref() != Some(value) {
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            if let Some(entries) = &json {
+                write_json_translations(&translation_path(&lang_path, locale, None)?, entries)?;
+            }
+            for (group, entries) in &php {
+                write_php_translations(
+                    &translation_path(&lang_path, locale, Some(group))?,
+                    entries,
+                )?;
+            }
+        }
+        updated.insert(locale.clone(), changed);
+    }
+
+    Ok(updated)
+}
+
+fn collect_php_groups(
+    dir: &Path,
+    locale_dir: &Path,
+    groups: &mut BTreeSet<String>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_php_groups(&path, locale_dir, groups)?;
+        } else if path.extension().is_some_and(|ext| ext == "php") {
+            groups.insert(php_group_name(&path, locale_dir));
+        }
+    }
+    Ok(())
+}
+
+/// The name `__()` uses for a PHP group file, e.g. `auth` or `admin/users`.
+fn php_group_name(path: &Path, locale_dir: &Path) -> String {
+    path.strip_prefix(locale_dir)
+        .unwrap_or(path)
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Parses RFC 4180 CSV: quoted fields may contain commas, newlines and `""` escapes.
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted field in the CSV file".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // Spreadsheet apps tend to leave blank lines at the end
+    rows.retain(|row| row.iter().any(|field| !field.is_empty()));
+    Ok(rows)
+}
+
+/// The `:name` placeholders in a translation. Laravel also accepts `:Name` and `:NAME` to
+/// change the case of the replacement, so they count as the same placeholder.
+fn placeholders(value: &str) -> BTreeSet<String> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut found = BTreeSet::new();
+    for (i, c) in chars.iter().enumerate() {
+        // Skip times like `10:30` and the colon in `Note: ...`
+        let starts_token = *c == ':'
+            && (i == 0 || !chars[i - 1].is_alphanumeric())
+            && chars
+                .get(i + 1)
+                .is_some_and(|c| c.is_alphabetic() || *c == '_');
+        if starts_token {
+            let name: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .collect();
+            found.insert(name.to_lowercase());
+        }
+    }
+    found
+}
+
+/// Locales in the project's lang folder, from both `<locale>/` folders and `<locale>.json`
+/// files.
+fn project_locales(lang_path: &Path) -> Vec<String> {
+    let mut locales = BTreeSet::new();
+    if let Ok(entries) = fs::read_dir(lang_path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() && name != "vendor" {
+                locales.insert(name);
+            } else if let Some(locale) = name.strip_suffix(".json") {
+                locales.insert(locale.to_string());
+            }
+        }
+    }
+    locales.into_iter().collect()
+}
+
+/// Every translation of a locale keyed the way `__()` looks them up: `group.key` for PHP
+/// group files (`admin/users.key` in subfolders) and the plain key for JSON.
+fn load_locale_translations(
+    lang_path: &Path,
+    locale: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut translations = BTreeMap::new();
+
+    let locale_dir = lang_path.join(locale);
+    if locale_dir.is_dir() {
+        load_php_groups(&locale_dir, &locale_dir, &mut translations)?;
+    }
+
+    let json_path = lang_path.join(format!("{}.json", locale));
+    for (key, value) in read_json_translations(&json_path)? {
+        if let serde_json::Value::String(value) = value {
+            translations.insert(key, value);
+        }
+    }
+
+    Ok(translations)
+}
+
+fn load_php_groups(
+    dir: &Path,
+    locale_dir: &Path,
+    translations: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            load_php_groups(&path, locale_dir, translations)?;
+            continue;
+        }
+        if path.extension().is_none_or(|ext| ext != "php") {
+            continue;
+        }
+
+        let group = php_group_name(&path, locale_dir);
+        for (key, value) in read_php_translations(&path)? {
+            translations.insert(format!("{}.{}", group, key), value);
+        }
+    }
+    Ok(())
+}
+
+/// The locale's JSON file, or the given PHP group file in the locale folder.
+fn translation_path(lang_path: &Path, locale: &str, file: Option<&str>) -> Result<PathBuf, String> {
+    if !valid_locale(locale) {
+        return Err("Invalid locale".to_string());
+    }
+    let Some(file) = file else {
+        return Ok(lang_path.join(format!("{}.json", locale)));
+    };
+
+    if file.is_empty() || file.contains("..") {
+        return Err("Invalid file path".to_string());
+    }
+    let file_name = if file.ends_with(".php") {
+        file.to_string()
+    } else {
+        format!("{}.php", file)
+    };
+    Ok(lang_path.join(locale).join(file_name))
+}
+
+/// Translations from a PHP group file, or none if it doesn't exist yet.
+fn read_php_translations(path: &Path) -> Result<Vec<(String, String)>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_php_lang(&content)
+        .map(|lang| lang.entries)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_php_translations(path: &Path, entries: &[(String, String)]) -> Result<(), String> {
+    write_lang_file(path, &render_php_lang(entries)?)
+}
+
+fn write_lang_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_atomic(path, content).map_err(|e| e.to_string())
+}
+
+/// Sets `key` in a list of translations, keeping its position if it's already there.
+/// Returns the previous value.
+fn set_entry(entries: &mut Vec<(String, String)>, key: String, value: String) -> Option<String> {
+    match entries.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, existing)) => Some(std::mem::replace(existing, value)),
+        None => {
+            entries.push((key, value));
+            None
+        }
+    }
+}
+
+/// Translations from a locale's JSON file, or none if it doesn't exist yet.
+fn read_json_translations(
+    path: &Path,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if !path.exists() {
+        return Ok(serde_json::Map::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if content.trim().is_empty() {
+        return Ok(serde_json::Map::new());
+    }
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_json_translations(
+    path: &Path,
+    entries: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    // Four-space indentation, like Laravel's own lang files
+    let mut content = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    serde::Serialize::serialize(entries, &mut serializer).map_err(|e| e.to_string())?;
+    content.push(b'\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = String::from_utf8(content).map_err(|e| e.to_string())?;
+    write_atomic(path, &content).map_err(|e| e.to_string())
+}
+
+/// Parses a PHP translation file into a flat map of dotted keys, e.g. `auth.failed`.
+#[command]
+pub fn parse_lang_php(id: String, file_path: String) -> Result<BTreeMap<String, String>, String> {
+    let project_path = project_location(&id)?;
+
+    if file_path.contains("..") {
+        return Err("Invalid file path".to_string());
+    }
+
+    let content = fs::read_to_string(project_path.join(&file_path)).map_err(|e| e.to_string())?;
+    Ok(parse_php_lang(&content)?.entries.into_iter().collect())
+}
+
+/// Saves a flat map of dotted keys as a PHP translation file, nesting arrays by key. Keys
+/// already in the file keep their order; new ones are added after them alphabetically.
+#[command]
+pub fn write_lang_php(
+    id: String,
+    file_path: String,
+    mut entries: HashMap<String, String>,
+) -> Result<(), String> {
+    let project_path = project_location(&id)?;
+
+    if file_path.contains("..") {
+        return Err("Invalid file path".to_string());
+    }
+    if !file_path.ends_with(".php") {
+        return Err("Not a PHP file".to_string());
+    }
+
+    let path = project_path.join(&file_path);
+    let existing = read_php_translations(&path).unwrap_or_default();
+    let mut ordered: Vec<(String, String)> = existing
+        .into_iter()
+        .filter_map(|(key, _)| entries.remove(&key).map(|value| (key, value)))
+        .collect();
+    let mut added: Vec<(String, String)> = entries.into_iter().collect();
+    added.sort();
+    ordered.extend(added);
+
+    let content = render_php_lang(&ordered)?;
+    write_atomic(&path, &content).map_err(|e| e.to_string())
+}
+
+/// Writes to a temporary file next to `path` and renames it over the original, so a crash
+/// can't leave a half-written file behind.
+fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "No file name"))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+enum LangNode {
+    Value(String),
+    Array(Vec<(String, LangNode)>),
+}
+
+/// Renders dotted keys as a `<?php return [...];` file, keeping the order they come in.
+fn render_php_lang(entries: &[(String, String)]) -> Result<String, String> {
+    let mut root = Vec::new();
+    for (key, value) in entries {
+        let parts = key_parts(key)?;
+
+        let mut node = &mut root;
+        for (i, part) in parts.iter().enumerate() {
+            let last = i == parts.len() - 1;
+            let index = match node.iter().position(|(k, _)| k == part) {
+                Some(_) if last => {
+                    return Err(format!("Conflicting translation key '{}'", key));
+                }
+                Some(index) => index,
+                None => {
+                    let child = if last {
+                        LangNode::Value(value.clone())
+                    } else {
+                        LangNode::Array(Vec::new())
+                    };
+                    node.push((part.to_string(), child));
+                    node.len() - 1
+                }
+            };
+            if last {
+                break;
+            }
+            node = match &mut node[index].1 {
+                LangNode::Array(children) => children,
+                LangNode::Value(_) => {
+                    return Err(format!("Conflicting translation key '{}'", key));
+                }
+            };
+        }
+    }
+
+    let mut out = String::from("<?php\n\nreturn ");
+    render_php_array(&root, 0, &mut out);
+    out.push_str(";\n");
+    Ok(out)
+}
+
+fn render_php_array(array: &[(String, LangNode)], depth: usize, out: &mut String) {
+    if array.is_empty() {
+        out.push_str("[]");
+        return;
+    }
+
+    let indent = "    ".repeat(depth + 1);
+    out.push_str("[\n");
+    for (key, node) in array {
+        out.push_str(&indent);
+        out.push_str(&php_key(key));
+        out.push_str(" => ");
+        match node {
+            LangNode::Value(value) => out.push_str(&php_string(value)),
+            LangNode::Array(children) => render_php_array(children, depth + 1, out),
+        }
+        out.push_str(",\n");
+    }
+    out.push_str(&"    ".repeat(depth));
+    out.push(']');
+}
+
+fn php_key(key: &str) -> String {
+    match integer_key(key) {
+        Some(index) => index.to_string(),
+        None => php_string(key),
+    }
+}
+
+/// PHP turns canonical integer strings like `"5"` (but not `"05"`) into integer keys.
+fn integer_key(key: &str) -> Option<u64> {
+    key.parse::<u64>()
+        .ok()
+        .filter(|index| index.to_string() == key)
+}
+
+fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn key_parts(key: &str) -> Result<Vec<&str>, String> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(format!("Invalid translation key '{}'", key));
+    }
+    Ok(parts)
+}
+
+/// Sets one dotted key in the source of a PHP lang file. An existing value is replaced where
+/// it stands; a new key is added at the end of the deepest array that already exists for it.
+/// Everything else in the file is left as written.
+fn patch_php_lang(content: &str, key: &str, value: &str) -> Result<String, String> {
+    let parts = key_parts(key)?;
+    let lang = parse_php_lang(content)?;
+    let mut chars: Vec<char> = content.chars().collect();
+
+    if let Some(span) = lang.values.get(key) {
+        chars.splice(span.clone(), php_string(value).chars());
+        return Ok(chars.into_iter().collect());
+    }
+
+    let conflict = || format!("Conflicting translation key '{}'", key);
+    if lang.arrays.contains_key(key)
+        || (1..parts.len()).any(|i| lang.values.contains_key(&parts[..i].join(".")))
+    {
+        return Err(conflict());
+    }
+    let depth = (0..parts.len())
+        .rev()
+        .find(|i| lang.arrays.contains_key(&parts[..*i].join(".")))
+        .ok_or_else(conflict)?;
+    let array = &lang.arrays[&parts[..depth].join(".")];
+
+    // Indent new entries one level past the line the array closes on
+    let line_start = chars[..array.close]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map_or(0, |i| i + 1);
+    let close_indent: String = chars[line_start..array.close]
+        .iter()
+        .take_while(|c| **c == ' ' || **c == '\t')
+        .collect();
+    let indent = format!("{}    ", close_indent);
+
+    let mut node = LangNode::Value(value.to_string());
+    for part in parts[depth + 1..].iter().rev() {
+        node = LangNode::Array(vec![(part.to_string(), node)]);
+    }
+    let mut entry = format!("\n{}{} => ", indent, php_key(parts[depth]));
+    match &node {
+        LangNode::Value(value) => entry.push_str(&php_string(value)),
+        LangNode::Array(children) => render_php_array(children, indent.len() / 4, &mut entry),
+    }
+    entry.push(',');
+
+    // After the last element and any comment that follows it, before the closing bracket
+    let insert_at = chars[..array.close]
+        .iter()
+        .rposition(|c| !c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    if !chars[insert_at..array.close].contains(&'\n') {
+        entry.push('\n');
+        entry.push_str(&close_indent);
+    }
+    chars.splice(insert_at..insert_at, entry.chars());
+    if let Some(last_end) = array.last_end.filter(|_| !array.trailing_comma) {
+        chars.insert(last_end, ',');
+    }
+    Ok(chars.into_iter().collect())
+}
+
+/// A parsed PHP lang file. Positions are char offsets into the source, so single edits can be
+/// made without rendering the file again.
+#[derive(Default)]
+struct PhpLang {
+    entries: Vec<(String, String)>, // Dotted keys in source order
+    values: HashMap<String, Range<usize>>,
+    arrays: HashMap<String, PhpArray>, // By dotted key; the returned array is ""
+}
+
+struct PhpArray {
+    close: usize,            // The closing `]` or `)`
+    last_end: Option<usize>, // End of the last element; None for an empty array
+    trailing_comma: bool,
+}
+
+/// Parses a `<?php return [...];` file. Only literal arrays of strings and numbers are
+/// supported; anything that needs PHP to evaluate is an error.
+fn parse_php_lang(content: &str) -> Result<PhpLang, String> {
+    let mut parser = PhpLangParser {
+        chars: content.chars().collect(),
+        pos: 0,
+    };
+
+    parser.skip_whitespace();
+    if !parser.eat("<?php") {
+        return Err("Expected the file to start with <?php".to_string());
+    }
+    parser.skip_trivia()?;
+    if parser.eat_keyword("declare") {
+        // e.g. `declare(strict_types=1);`
+        while !parser.eat(";") {
+            if parser.peek().is_none() {
+                return Err(parser.error("Unterminated declare statement"));
+            }
+            parser.pos += 1;
+        }
+        parser.skip_trivia()?;
+    }
+    if !parser.eat_keyword("return") {
+        return Err(parser.error("Expected `return [...]`"));
+    }
+
+    let mut lang = PhpLang::default();
+    parser.skip_trivia()?;
+    parser.parse_array("", &mut lang)?;
+    parser.skip_trivia()?;
+    if !parser.eat(";") {
+        return Err(parser.error("Expected `;` after the array"));
+    }
+    parser.skip_trivia()?;
+    parser.eat("?>");
+    parser.skip_trivia()?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("Unexpected code after the returned array"));
+    }
+
+    Ok(lang)
+}
+
+struct PhpLangParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl PhpLangParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.starts_with(s) {
+            self.pos += s.chars().count();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Like `eat`, but case-insensitive and only for a whole word.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let len = keyword.chars().count();
+        let Some(word) = self.chars.get(self.pos..self.pos + len) else {
+            return false;
+        };
+        let word: String = word.iter().collect();
+        let boundary = self
+            .chars
+            .get(self.pos + len)
+            .is_none_or(|c| !c.is_alphanumeric() && *c != '_');
+        if boundary && word.eq_ignore_ascii_case(keyword) {
+            self.pos += len;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count()
+            + 1;
+        format!("{} on line {}", message, line)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.eat("//") || self.eat("#") {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if self.eat("/*") {
+                while !self.eat("*/") {
+                    if self.peek().is_none() {
+                        return Err(self.error("Unterminated comment"));
+                    }
+                    self.pos += 1;
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses `[...]` or `array(...)`, adding its values to `lang` under `prefix`.
+    fn parse_array(&mut self, prefix: &str, lang: &mut PhpLang) -> Result<(), String> {
+        let close = if self.eat("[") {
+            "]"
+        } else if self.eat_keyword("array") {
+            self.skip_trivia()?;
+            if !self.eat("(") {
+                return Err(self.error("Expected `(` after `array`"));
+            }
+            ")"
+        } else {
+            return Err(self.error("Expected an array"));
+        };
+
+        let mut array = PhpArray {
+            close: 0,
+            last_end: None,
+            trailing_comma: false,
+        };
+        let mut next_index = 0u64;
+        loop {
+            self.skip_trivia()?;
+            if self.starts_with(close) {
+                array.close = self.pos;
+                self.pos += 1;
+                lang.arrays.insert(prefix.to_string(), array);
+                return Ok(());
+            }
+
+            // Either `key => value` or just a value, which gets the next integer key
+            let mut key = None;
+            if !self.at_array() {
+                let start = self.pos;
+                let candidate = self.parse_string()?;
+                self.skip_trivia()?;
+                if self.eat("=>") {
+                    key = Some(candidate);
+                    self.skip_trivia()?;
+                } else {
+                    self.pos = start;
+                }
+            }
+            let key = match key {
+                Some(key) => {
+                    if let Ok(index) = key.parse::<u64>() {
+                        next_index = next_index.max(index + 1);
+                    }
+                    key
+                }
+                None => {
+                    next_index += 1;
+                    (next_index - 1).to_string()
+                }
+            };
+
+            let full_key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if self.at_array() {
+                self.parse_array(&full_key, lang)?;
+            } else {
+                let start = self.pos;
+                let value = self.parse_string()?;
+                lang.values.insert(full_key.clone(), start..self.pos);
+                set_entry(&mut lang.entries, full_key, value);
+            }
+            array.last_end = Some(self.pos);
+
+            self.skip_trivia()?;
+            array.trailing_comma = self.eat(",");
+            if !array.trailing_comma {
+                self.skip_trivia()?;
+                if !self.starts_with(close) {
+                    return Err(self.error(&format!("Expected `,` or `{}`", close)));
+                }
+                array.close = self.pos;
+                self.pos += 1;
+                lang.arrays.insert(prefix.to_string(), array);
+                return Ok(());
+            }
+        }
+    }
+
+    fn at_array(&mut self) -> bool {
+        let start = self.pos;
+        let found = self.eat("[") || self.eat_keyword("array");
+        self.pos = start;
+        found
+    }
+
+    /// Parses a string literal or number, joining `'a' . 'b'` concatenations.
+    fn parse_string(&mut self) -> Result<String, String> {
+        let mut value = self.parse_scalar()?;
+        loop {
+            let before = self.pos;
+            self.skip_trivia()?;
+            if self.peek() == Some('.') && !self.starts_with(".=") {
+                self.pos += 1;
+                self.skip_trivia()?;
+                value.push_str(&self.parse_scalar()?);
+            } else {
+                self.pos = before;
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_scalar(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some('\'') => self.parse_single_quoted(),
+            Some('"') => self.parse_double_quoted(),
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                let start = self.pos;
+                self.pos += 1;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+                {
+                    self.pos += 1;
+                }
+                let number: String = self.chars[start..self.pos].iter().collect();
+                let number = number.replace('_', "");
+                if number.parse::<i64>().is_err() && number.parse::<f64>().is_err() {
+                    return Err(self.error(&format!("Unsupported number `{}`", number)));
+                }
+                Ok(number)
+            }
+            _ => Err(self.error("Expected a string; only literal translations are supported")),
+        }
+    }
+
+    fn parse_single_quoted(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("Unterminated string")),
+                Some('\'') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') if matches!(self.chars.get(self.pos + 1), Some('\'' | '\\')) => {
+                    value.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_double_quoted(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        // Bytes rather than chars, since \x and octal escapes can spell out UTF-8 sequences
+        let mut value = Vec::new();
+        let mut buf = [0; 4];
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("Unterminated string"));
+            };
+            self.pos += 1;
+            match c {
+                '"' => {
+                    return String::from_utf8(value)
+                        .map_err(|_| self.error("Escape sequences in string are not valid UTF-8"));
+                }
+                '$' if self
+                    .peek()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '{') =>
+                {
+                    return Err(self.error("Variables in strings are not supported"));
+                }
+                '{' if self.peek() == Some('$') => {
+                    return Err(self.error("Variables in strings are not supported"));
+                }
+                '\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err(self.error("Unterminated string"));
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        'n' => value.push(b'\n'),
+                        't' => value.push(b'\t'),
+                        'r' => value.push(b'\r'),
+                        'v' => value.push(0x0b),
+                        'e' => value.push(0x1b),
+                        'f' => value.push(0x0c),
+                        '\\' | '$' | '"' => value.push(escaped as u8),
+                        'u' if self.peek() == Some('{') => {
+                            let end = self.chars[self.pos..]
+                                .iter()
+                                .position(|c| *c == '}')
+                                .ok_or_else(|| self.error("Unterminated \\u{...} escape"))?;
+                            let hex: String =
+                                self.chars[self.pos + 1..self.pos + end].iter().collect();
+                            let decoded = u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("Invalid \\u{...} escape"))?;
+                            value.extend(decoded.encode_utf8(&mut buf).as_bytes());
+                            self.pos += end + 1;
+                        }
+                        'x' if self.peek().is_some_and(|c| c.is_ascii_hexdigit()) => {
+                            let digits = self.take_digits(2, 16);
+                            value.push(u8::from_str_radix(&digits, 16).unwrap_or(0));
+                        }
+                        '0'..='7' => {
+                            self.pos -= 1;
+                            let digits = self.take_digits(3, 8);
+                            // PHP wraps octal values above \377 to a byte
+                            value.push((u32::from_str_radix(&digits, 8).unwrap_or(0) & 0xFF) as u8);
+                        }
+                        // PHP keeps unknown escapes as written
+                        other => {
+                            value.push(b'\\');
+                            value.extend(other.encode_utf8(&mut buf).as_bytes());
+                        }
+                    }
+                }
+                c => value.extend(c.encode_utf8(&mut buf).as_bytes()),
+            }
+        }
+    }
+
+    fn take_digits(&mut self, max: usize, radix: u32) -> String {
+        let start = self.pos;
+        while self.pos - start < max && self.peek().is_some_and(|c| c.is_digit(radix)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTH: &str = "<?php
+
+return [
+    // Shown when the login fails
+    'failed' => 'These credentials do not match our records.',
+    'throttle' => 'Too many login attempts.',
+    'password' => [
+        'reset' => 'Your password has been reset.'
+    ],
+];
+";
+
+    #[test]
+    fn patch_replaces_an_existing_value_in_place() {
+        let patched = patch_php_lang(AUTH, "failed", "Wrong email or password.").unwrap();
+        assert_eq!(
+            patched,
+            AUTH.replace(
+                "'These credentials do not match our records.'",
+                "'Wrong email or password.'"
+            )
+        );
+    }
+
+    #[test]
+    fn patch_appends_new_keys_to_their_array() {
+        let patched = patch_php_lang(AUTH, "password.sent", "Check your email.").unwrap();
+        assert!(patched.contains(
+            "        'reset' => 'Your password has been reset.',\n        'sent' => 'Check your email.',\n    ],"
+        ));
+        assert!(patched.contains("// Shown when the login fails"));
+
+        let patched = patch_php_lang(AUTH, "verify.notice", "Please verify.").unwrap();
+        assert!(patched.ends_with(
+            "    ],\n    'verify' => [\n        'notice' => 'Please verify.',\n    ],\n];\n"
+        ));
+        let keys: Vec<String> = parse_php_lang(&patched)
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(
+            keys,
+            ["failed", "throttle", "password.reset", "verify.notice"]
+        );
+    }
+
+    #[test]
+    fn patch_fills_an_empty_array() {
+        let patched = patch_php_lang("<?php\n\nreturn [];\n", "welcome", "Hi").unwrap();
+        assert_eq!(patched, "<?php\n\nreturn [\n    'welcome' => 'Hi',\n];\n");
+    }
+
+    #[test]
+    fn patch_rejects_keys_that_clash_with_the_nesting() {
+        assert!(patch_php_lang(AUTH, "password", "x").is_err());
+        assert!(patch_php_lang(AUTH, "failed.reason", "x").is_err());
+    }
+
+    #[test]
+    fn double_quoted_escapes_are_decoded_as_bytes() {
+        let lang = parse_php_lang(
+            r#"<?php return ['hex' => "caf\xC3\xA9", 'octal' => "caf\303\251", 'wrapped' => "a\501"];"#,
+        )
+        .unwrap();
+        assert_eq!(
+            lang.entries,
+            [
+                ("hex".to_string(), "café".to_string()),
+                ("octal".to_string(), "café".to_string()),
+                ("wrapped".to_string(), "aA".to_string()),
+            ]
+        );
+        assert!(parse_php_lang(r#"<?php return ['latin1' => "caf\xE9"];"#).is_err());
+    }
+
+    #[test]
+    fn render_keeps_the_given_order() {
+        let entries = vec![
+            ("zeta".to_string(), "Z".to_string()),
+            ("alpha.b".to_string(), "B".to_string()),
+            ("alpha.a".to_string(), "A".to_string()),
+        ];
+        assert_eq!(
+            render_php_lang(&entries).unwrap(),
+            "<?php\n\nreturn [\n    'zeta' => 'Z',\n    'alpha' => [\n        'b' => 'B',\n        'a' => 'A',\n    ],\n];\n"
+        );
+    }
+}
@@ -0,0 +1,576 @@
+use crate::commands::project_commands::{configure_command_env, read_project_json, run_streamed};
+use crate::utils::{parse_env, project_location};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub name: String,
+    pub batch: Option<u32>,
+    pub ran: bool,
+}
+
+/// One artisan command run by `clear_all_caches`.
+#[derive(Debug, Serialize)]
+pub struct ArtisanStep {
+    pub command: String,
+    pub success: bool,
+    pub output: String, // stdout and stderr combined
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLinkStatus {
+    Linked,
+    AlreadyLinked,
+}
+
+/// An Eloquent model found by `get_models`.
+#[derive(Debug, Serialize)]
+pub struct EloquentModel {
+    pub name: String,
+    pub class: String, // Fully qualified, e.g. App\Models\User
+    pub path: String,
+}
+
+/// What `fix_permissions` changed.
+#[derive(Debug, Default, Serialize)]
+pub struct PermissionsFix {
+    pub changed: Vec<String>, // Relative to the project folder
+    pub skipped: Vec<String>, // Directories that don't exist
+    pub note: Option<String>,
+}
+
+/// Looks up a project's folder and checks that it has an artisan script.
+fn laravel_project_location(project_id: &str) -> Result<PathBuf, String> {
+    let location = project_location(project_id)?;
+    if !location.join("artisan").exists() {
+        return Err("Artisan not found".to_string());
+    }
+    Ok(location)
+}
+
+fn php_not_found(e: std::io::Error) -> String {
+    format!(
+        "Failed to execute php artisan: {}. Make sure PHP is installed and in your PATH.",
+        e
+    )
+}
+
+/// Runs `php artisan <args>` to completion and captures its output.
+fn artisan_output(location: &Path, args: &[&str]) -> Result<std::process::Output, String> {
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(location).arg("artisan").args(args);
+    configure_command_env(&mut cmd);
+    cmd.output().map_err(php_not_found)
+}
+
+/// Runs `php artisan <args>` in the project folder, streaming output to
+/// `artisan-output-<project_id>`. Returns the exit code.
+#[command(async)]
+pub fn run_artisan(
+    project_id: String,
+    args: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<i32, String> {
+    let location = laravel_project_location(&project_id)?;
+
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(&location).arg("artisan").args(&args);
+
+    run_streamed(
+        cmd,
+        &project_id,
+        format!("artisan {}", args.join(" ")),
+        &format!("artisan-output-{}", project_id),
+        &app_handle,
+        php_not_found,
+    )
+}
+
+/// Runs pending migrations (`migrate --force`, since there's no terminal to confirm in),
+/// streaming output like `run_artisan`. Returns the exit code.
+#[command(async)]
+pub fn run_migrations(project_id: String, app_handle: AppHandle) -> Result<i32, String> {
+    let args = vec!["migrate".to_string(), "--force".to_string()];
+    run_artisan(project_id, args, app_handle)
+}
+
+/// Rolls back the last `step` migrations. Data in the dropped tables is lost, so the UI must
+/// ask first and pass `confirm`.
+#[command(async)]
+pub fn rollback_migrations(
+    project_id: String,
+    step: u32,
+    confirm: bool,
+    app_handle: AppHandle,
+) -> Result<i32, String> {
+    if !confirm {
+        return Err("Rolling back migrations must be confirmed".to_string());
+    }
+    if step == 0 {
+        return Err("Step must be at least 1".to_string());
+    }
+    let args = vec![
+        "migrate:rollback".to_string(),
+        format!("--step={}", step),
+        "--force".to_string(),
+    ];
+    run_artisan(project_id, args, app_handle)
+}
+
+/// Runs `db:seed --force`, or just `class` when given, streaming output like `run_artisan`.
+/// Seeder exceptions arrive as `stderr` lines. Returns the exit code.
+#[command(async)]
+pub fn run_seeder(
+    project_id: String,
+    class: Option<String>,
+    app_handle: AppHandle,
+) -> Result<i32, String> {
+    let location = laravel_project_location(&project_id)?;
+    check_database_configured(&location)?;
+
+    let mut args = vec!["db:seed".to_string(), "--force".to_string()];
+    if let Some(class) = class.filter(|c| !c.is_empty()) {
+        // Namespaced classes like Database\Seeders\UserSeeder are allowed
+        if !class
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '\\')
+        {
+            return Err(format!("Invalid seeder class '{}'", class));
+        }
+        args.push(format!("--class={}", class));
+    }
+    run_artisan(project_id, args, app_handle)
+}
+
+/// Fails with a clear message when `.env` doesn't say which database to use, or points at an
+/// SQLite file that doesn't exist, instead of letting artisan fail halfway through.
+fn check_database_configured(root: &Path) -> Result<(), String> {
+    let env = std::fs::read_to_string(root.join(".env"))
+        .map(|content| parse_env(&content))
+        .map_err(|_| "The project has no .env file. Configure the database first.".to_string())?;
+    let value = |key: &str| env.get(key).filter(|v| !v.is_empty());
+
+    if value("DB_URL").is_some() || value("DATABASE_URL").is_some() {
+        return Ok(());
+    }
+    match value("DB_CONNECTION").map(String::as_str) {
+        None => Err("DB_CONNECTION isn't set in .env. Configure the database first.".to_string()),
+        Some("sqlite") => {
+            // Laravel falls back to database/database.sqlite
+            let database = value("DB_DATABASE")
+                .map(|d| root.join(d))
+                .unwrap_or_else(|| root.join("database").join("database.sqlite"));
+            if database.exists() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "The SQLite database {} doesn't exist. Run the migrations first.",
+                    database.display()
+                ))
+            }
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Creates a migration with `make:migration` and returns the new file's full path. With
+/// `create_table` the migration creates `table` (guessed from the name when not given);
+/// otherwise a `table` makes it alter that table.
+#[command(async)]
+pub fn make_migration(
+    project_id: String,
+    name: String,
+    create_table: bool,
+    table: Option<String>,
+) -> Result<String, String> {
+    let identifier = |value: &str, extra: char| {
+        value
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == extra)
+    };
+    if !identifier(&name, '_') {
+        return Err(format!(
+            "Invalid migration name '{}'. Use letters, numbers and underscores.",
+            name
+        ));
+    }
+    let table = table.filter(|t| !t.is_empty());
+    if let Some(table) = table.as_deref().filter(|t| !identifier(t, '.')) {
+        return Err(format!("Invalid table name '{}'", table));
+    }
+
+    let location = laravel_project_location(&project_id)?;
+    let mut args = vec!["make:migration".to_string(), name, "--no-ansi".to_string()];
+    match (create_table, table) {
+        (true, Some(table)) => args.push(format!("--create={}", table)),
+        (true, None) => args.push("--create".to_string()),
+        (false, Some(table)) => args.push(format!("--table={}", table)),
+        (false, None) => {}
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = artisan_output(&location, &args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}\n{}", stdout.trim(), stderr.trim())
+            .trim()
+            .to_string());
+    }
+
+    let file = parse_created_migration(&stdout)
+        .ok_or_else(|| format!("Couldn't find the new migration in: {}", stdout.trim()))?;
+    Ok(location.join(file).display().to_string())
+}
+
+/// Finds the file in `Migration [database/migrations/<file>.php] created successfully.`, or
+/// in `Created Migration: <file>` from Laravel 8 and older.
+fn parse_created_migration(output: &str) -> Option<String> {
+    output.lines().map(str::trim).find_map(|line| {
+        if let Some(name) = line.strip_prefix("Created Migration:") {
+            return Some(format!("database/migrations/{}.php", name.trim()));
+        }
+        let start = line.find('[')?;
+        let end = line[start..].find(']')? + start;
+        let path = &line[start + 1..end];
+        path.ends_with(".php").then(|| path.to_string())
+    })
+}
+
+/// Runs `config:clear`, `cache:clear`, `route:clear` and `view:clear` in order. Stops at the
+/// first command that fails; the steps that ran, including the failed one, are returned.
+#[command(async)]
+pub fn clear_all_caches(project_id: String) -> Result<Vec<ArtisanStep>, String> {
+    let location = laravel_project_location(&project_id)?;
+
+    let mut steps = Vec::new();
+    for command in ["config:clear", "cache:clear", "route:clear", "view:clear"] {
+        let output = artisan_output(&location, &[command, "--no-ansi"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let success = output.status.success();
+        steps.push(ArtisanStep {
+            command: command.to_string(),
+            success,
+            output: format!("{}\n{}", stdout.trim(), stderr.trim())
+                .trim()
+                .to_string(),
+        });
+        if !success {
+            break;
+        }
+    }
+    Ok(steps)
+}
+
+/// Runs `php artisan storage:link`. A `public/storage` link that already exists is reported as
+/// `AlreadyLinked` rather than as an error.
+#[command(async)]
+pub fn storage_link(project_id: String) -> Result<StorageLinkStatus, String> {
+    let location = laravel_project_location(&project_id)?;
+    let link = location.join("public").join("storage");
+    if link
+        .symlink_metadata()
+        .is_ok_and(|meta| meta.file_type().is_symlink())
+    {
+        return Ok(StorageLinkStatus::AlreadyLinked);
+    }
+
+    let output = artisan_output(&location, &["storage:link", "--no-ansi"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = format!("{}\n{}", stdout.trim(), stderr.trim());
+    // Laravel exits with 0 when public/storage exists, which here means it isn't a link
+    if !output.status.success() || message.contains("already exists") {
+        return Err(message.trim().to_string());
+    }
+    Ok(StorageLinkStatus::Linked)
+}
+
+/// Makes `storage` and `bootstrap/cache` writable by the owner and group (0775 for folders,
+/// 0664 for files), recursively. Symlinks aren't followed, and a directory that resolves to
+/// somewhere outside the project is refused. Nothing is changed on Windows.
+#[command(async)]
+pub fn fix_permissions(project_id: String) -> Result<PermissionsFix, String> {
+    let location = laravel_project_location(&project_id)?;
+    let mut fix = PermissionsFix::default();
+    if cfg!(not(unix)) {
+        fix.note = Some("Windows doesn't use Unix permissions, so nothing was changed".to_string());
+        return Ok(fix);
+    }
+
+    let root = location.canonicalize().map_err(|e| e.to_string())?;
+    for dir in ["storage", "bootstrap/cache"] {
+        let Ok(path) = root.join(dir).canonicalize() else {
+            fix.skipped.push(dir.to_string());
+            continue;
+        };
+        if !path.starts_with(&root) {
+            return Err(format!(
+                "{} points outside the project, so its permissions weren't changed",
+                dir
+            ));
+        }
+        make_writable(&root, &path, &mut fix.changed)?;
+    }
+    Ok(fix)
+}
+
+#[cfg(unix)]
+fn make_writable(
+    root: &std::path::Path,
+    path: &std::path::Path,
+    changed: &mut Vec<String>,
+) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string();
+    let meta = std::fs::symlink_metadata(path).map_err(|e| format!("{}: {}", relative, e))?;
+    if meta.file_type().is_symlink() {
+        return Ok(());
+    }
+    let wanted = if meta.is_dir() { 0o775 } else { 0o664 };
+    let mode = meta.permissions().mode() & 0o7777;
+    if mode & wanted != wanted {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode | wanted))
+            .map_err(|e| format!("Couldn't change the permissions of {}: {}", relative, e))?;
+        changed.push(relative.clone());
+    }
+
+    if meta.is_dir() {
+        let entries = std::fs::read_dir(path).map_err(|e| format!("{}: {}", relative, e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("{}: {}", relative, e))?;
+            make_writable(root, &entry.path(), changed)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_writable(
+    _root: &std::path::Path,
+    _path: &std::path::Path,
+    _changed: &mut Vec<String>,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Base classes a model can extend. Users extend `Authenticatable`, which extends `Model`.
+const MODEL_BASE_CLASSES: [&str; 4] = ["Model", "Authenticatable", "Pivot", "MorphPivot"];
+
+/// Lists the project's Eloquent models by scanning the source rather than booting the app.
+/// Looks in `app/Models`, the `app` folder itself where models lived before Laravel 8, and the
+/// folder set as `models_path` in the project config. Sorted by class name.
+#[command(async)]
+pub fn get_models(project_id: String) -> Result<Vec<EloquentModel>, String> {
+    let location = laravel_project_location(&project_id)?;
+    let root = location.as_path();
+
+    let config = read_project_json(&location.join(".workshop/project.json").to_string_lossy())?;
+    let mut folders = vec![
+        (root.join("app").join("Models"), true),
+        (root.join("app"), false),
+    ];
+    if let Some(custom) = config.get("models_path").and_then(|p| p.as_str()) {
+        folders.push((root.join(custom), true));
+    }
+
+    let mut files = Vec::new();
+    for (folder, recursive) in folders {
+        collect_php_files(&folder, recursive, &mut files);
+    }
+    files.sort();
+    files.dedup();
+
+    let mut models: Vec<EloquentModel> = files
+        .iter()
+        .filter_map(|path| {
+            let source = std::fs::read_to_string(path).ok()?;
+            let (namespace, name) = parse_model_class(&source)?;
+            Some(EloquentModel {
+                class: match namespace {
+                    Some(namespace) => format!("{}\\{}", namespace, name),
+                    None => name.clone(),
+                },
+                name,
+                path: path.display().to_string(),
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| a.class.cmp(&b.class));
+    Ok(models)
+}
+
+fn collect_php_files(
+    folder: &std::path::Path,
+    recursive: bool,
+    files: &mut Vec<std::path::PathBuf>,
+) {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if recursive {
+                collect_php_files(&path, true, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "php") {
+            files.push(path);
+        }
+    }
+}
+
+/// Finds `class X extends Model` (or another model base class, possibly fully qualified) and
+/// the file's namespace. Abstract base models are skipped.
+fn parse_model_class(source: &str) -> Option<(Option<String>, String)> {
+    let mut namespace = None;
+    for line in source.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("namespace ") {
+            namespace = Some(name.trim_end_matches([';', '{', ' ']).to_string());
+            continue;
+        }
+        let declaration = line
+            .strip_prefix("final ")
+            .or_else(|| line.strip_prefix("readonly "))
+            .unwrap_or(line);
+        let Some(rest) = declaration.strip_prefix("class ") else {
+            continue;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(name), Some("extends"), Some(parent)) =
+            (words.next(), words.next(), words.next())
+        else {
+            continue;
+        };
+        let parent = parent.trim_end_matches('{');
+        let parent = parent.rsplit('\\').next().unwrap_or(parent);
+        if MODEL_BASE_CLASSES.contains(&parent) {
+            return Some((namespace, name.to_string()));
+        }
+    }
+    None
+}
+
+/// Lists the project's migrations and whether each has run.
+#[command]
+pub fn get_migration_status(project_id: String) -> Result<Vec<MigrationStatus>, String> {
+    let location = laravel_project_location(&project_id)?;
+
+    // Newer Laravel versions can print JSON; older ones reject the option, so fall back to
+    // parsing the table
+    let output = artisan_output(&location, &["migrate:status", "--format=json"])?;
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if let Some(statuses) = parse_migration_json(&stdout) {
+            return Ok(statuses);
+        }
+    }
+
+    let output = artisan_output(&location, &["migrate:status", "--no-ansi"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = format!("{}\n{}", stderr.trim(), stdout.trim());
+        let message = message.trim();
+        let unreachable = ["SQLSTATE", "could not find driver", "Connection refused"];
+        if unreachable.iter().any(|marker| message.contains(marker)) {
+            return Err(format!(
+                "Could not connect to the project's database. Check the DB_* settings in .env.\n{}",
+                message
+            ));
+        }
+        return Err(message.to_string());
+    }
+
+    Ok(parse_migration_table(&stdout))
+}
+
+fn parse_migration_json(output: &str) -> Option<Vec<MigrationStatus>> {
+    let rows: Vec<serde_json::Value> = serde_json::from_str(output.trim()).ok()?;
+    rows.iter()
+        .map(|row| {
+            let name = row
+                .get("migration")
+                .or_else(|| row.get("name"))?
+                .as_str()?
+                .to_string();
+            let batch = row.get("batch").and_then(|b| {
+                b.as_u64()
+                    .map(|b| b as u32)
+                    .or_else(|| b.as_str()?.parse().ok())
+            });
+            let ran = match row.get("ran").or_else(|| row.get("status")) {
+                Some(serde_json::Value::Bool(ran)) => *ran,
+                Some(serde_json::Value::String(status)) => {
+                    matches!(status.to_lowercase().as_str(), "ran" | "yes")
+                }
+                _ => batch.is_some(),
+            };
+            Some(MigrationStatus { name, batch, ran })
+        })
+        .collect()
+}
+
+/// Parses both the `| Ran? | Migration | Batch |` table of older Laravel versions and the
+/// `name ....... [1] Ran` / `name ....... Pending` lines of newer ones.
+fn parse_migration_table(output: &str) -> Vec<MigrationStatus> {
+    let mut statuses = Vec::new();
+
+    for line in output.lines().map(str::trim) {
+        if let Some(row) = line.strip_prefix('|') {
+            let cells: Vec<&str> = row
+                .trim_end_matches('|')
+                .split('|')
+                .map(str::trim)
+                .collect();
+            if let [ran, name, batch] = cells[..] {
+                if ran == "Ran?" {
+                    continue;
+                }
+                statuses.push(MigrationStatus {
+                    name: name.to_string(),
+                    batch: batch.parse().ok(),
+                    ran: ran == "Yes",
+                });
+            }
+            continue;
+        }
+
+        let Some((name, rest)) = line.split_once(' ') else {
+            continue;
+        };
+        let status = rest.trim_start_matches([' ', '.']).trim();
+        if status == "Pending" {
+            statuses.push(MigrationStatus {
+                name: name.to_string(),
+                batch: None,
+                ran: false,
+            });
+        } else if let Some(batch) = status
+            .strip_suffix("Ran")
+            .map(str::trim)
+            .and_then(|b| b.strip_prefix('['))
+            .and_then(|b| b.strip_suffix(']'))
+        {
+            statuses.push(MigrationStatus {
+                name: name.to_string(),
+                batch: batch.parse().ok(),
+                ran: true,
+            });
+        }
+    }
+
+    statuses
+}
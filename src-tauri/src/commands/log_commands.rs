@@ -1,19 +1,34 @@
-use crate::database::Database;
-use crate::utils::get_db_path;
+use crate::state::AppState;
+use crate::utils::project_location;
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
-use tauri::command;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, State};
+
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_ENTRY_LIMIT: usize = 200;
+const MAX_SEARCH_MATCHES: usize = 500;
+
+/// One entry of a Laravel log, e.g.
+/// `[2024-01-01 12:00:00] local.ERROR: Something broke {"exception":"..."}` plus the stack
+/// trace lines that follow it.
+#[derive(Debug, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub environment: String,
+    pub level: String,
+    pub message: String,
+    pub stack_trace: Option<String>,
+}
 
 #[command]
 pub fn get_log_files(id: String) -> Result<Vec<String>, String> {
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-
-    let log_dir = Path::new(&project.location).join("storage/logs");
+    let log_dir = project_location(&id)?.join("storage/logs");
 
     if !log_dir.exists() {
         return Ok(vec![]);
@@ -42,27 +57,24 @@ pub fn get_log_files(id: String) -> Result<Vec<String>, String> {
     Ok(files)
 }
 
-#[command]
-pub fn read_log_file(id: String, filename: String) -> Result<String, String> {
-    let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-
+/// Resolves a log file inside the project's `storage/logs`, rejecting names that could escape it.
+fn log_file_path(id: &str, filename: &str) -> Result<PathBuf, String> {
     // Prevent directory traversal
     if filename.contains("..") || filename.contains("/") || filename.contains("\\") {
         return Err("Invalid filename".to_string());
     }
 
-    let log_path = Path::new(&project.location)
-        .join("storage/logs")
-        .join(filename);
+    let log_path = project_location(id)?.join("storage/logs").join(filename);
 
     if !log_path.exists() {
         return Err("Log file not found".to_string());
     }
+    Ok(log_path)
+}
+
+#[command]
+pub fn read_log_file(id: String, filename: String) -> Result<String, String> {
+    let log_path = log_file_path(&id, &filename)?;
 
     // Read the file. If it's too large, we might want to read only the last N lines, but for now read all.
     // Laravel logs can be large. Maybe limit to 1MB or something?
@@ -71,3 +83,321 @@ pub fn read_log_file(id: String, filename: String) -> Result<String, String> {
 
     fs::read_to_string(log_path).map_err(|e| e.to_string())
 }
+
+/// Empties a log file, keeping the file itself so Laravel carries on writing to it. Returns
+/// the number of bytes removed.
+#[command]
+pub fn clear_log_file(id: String, filename: String) -> Result<u64, String> {
+    let log_path = log_file_path(&id, &filename)?;
+    if !log_path.is_file() {
+        return Err("Log file not found".to_string());
+    }
+
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(&log_path)
+        .map_err(|e| e.to_string())?;
+    let removed = file.metadata().map_err(|e| e.to_string())?.len();
+    file.set_len(0).map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+/// Returns the newest entries whose level is one of `levels` (all levels if empty), newest
+/// first. The file is read backwards, so only the tail needed for `limit` matches is parsed.
+#[command]
+pub fn get_log_entries(
+    id: String,
+    filename: String,
+    levels: Vec<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let log_path = log_file_path(&id, &filename)?;
+    let limit = limit.unwrap_or(DEFAULT_ENTRY_LIMIT);
+    let levels: Vec<String> = levels.iter().map(|l| l.to_uppercase()).collect();
+
+    let file = fs::File::open(log_path).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    // Lines below a header belong to it; reading backwards we see them first
+    let mut continuation: Vec<String> = Vec::new();
+
+    for line in ReverseLines::new(file).map_err(|e| e.to_string())? {
+        if entries.len() >= limit {
+            break;
+        }
+        let line = line.map_err(|e| e.to_string())?;
+        let Some(mut entry) = parse_log_header(&line) else {
+            continuation.push(line);
+            continue;
+        };
+        if levels.is_empty() || levels.contains(&entry.level) {
+            continuation.reverse();
+            let trace = continuation.join("\n");
+            let trace = trace.trim_end();
+            if !trace.is_empty() {
+                entry.stack_trace = Some(trace.to_string());
+            }
+            entries.push(entry);
+        }
+        continuation.clear();
+    }
+
+    Ok(entries)
+}
+
+/// Parses a `[timestamp] environment.LEVEL: message` line. Returns `None` for lines that
+/// don't start an entry.
+fn parse_log_header(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.strip_prefix('[')?.split_once("] ")?;
+    if !timestamp.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let (channel, message) = rest.split_once(": ").or_else(|| rest.split_once(':'))?;
+    let (environment, level) = channel.split_once('.')?;
+    if level.is_empty() || !level.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        environment: environment.to_string(),
+        level: level.to_string(),
+        message: message.trim_end().to_string(),
+        stack_trace: None,
+    })
+}
+
+/// Iterates a file's lines from last to first, reading it in chunks from the end.
+struct ReverseLines {
+    file: fs::File,
+    pos: u64,
+    buf: Vec<u8>,
+}
+
+impl ReverseLines {
+    const CHUNK_SIZE: u64 = 64 * 1024;
+
+    fn new(file: fs::File) -> io::Result<Self> {
+        let pos = file.metadata()?.len();
+        Ok(ReverseLines {
+            file,
+            pos,
+            buf: Vec::new(),
+        })
+    }
+}
+
+impl Iterator for ReverseLines {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(newline) = self.buf.iter().rposition(|b| *b == b'\n') {
+                let line = self.buf.split_off(newline + 1);
+                self.buf.truncate(newline);
+                return Some(Ok(decode_line(&line)));
+            }
+            if self.pos == 0 {
+                if self.buf.is_empty() {
+                    return None;
+                }
+                let line = std::mem::take(&mut self.buf);
+                return Some(Ok(decode_line(&line)));
+            }
+
+            let size = Self::CHUNK_SIZE.min(self.pos);
+            self.pos -= size;
+            let mut chunk = vec![0; size as usize];
+            let read = self
+                .file
+                .seek(SeekFrom::Start(self.pos))
+                .and_then(|_| self.file.read_exact(&mut chunk));
+            if let Err(e) = read {
+                self.pos = 0;
+                self.buf.clear();
+                return Some(Err(e));
+            }
+            chunk.append(&mut self.buf);
+            self.buf = chunk;
+        }
+    }
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\r')
+        .to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogMatch {
+    pub filename: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogSearchResult {
+    pub matches: Vec<LogMatch>,
+    // Whether the search stopped early at the match cap
+    pub truncated: bool,
+}
+
+/// Searches every log in `storage/logs` for lines containing `query`, newest file first.
+#[command]
+pub fn search_logs(
+    id: String,
+    query: String,
+    case_sensitive: bool,
+) -> Result<LogSearchResult, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let needle = if case_sensitive {
+        query
+    } else {
+        query.to_lowercase()
+    };
+
+    let mut matches = Vec::new();
+    for filename in get_log_files(id.clone())? {
+        let log_path = log_file_path(&id, &filename)?;
+        let file = fs::File::open(log_path).map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        let mut line_number = 0;
+
+        loop {
+            buf.clear();
+            if reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| e.to_string())?
+                == 0
+            {
+                break;
+            }
+            line_number += 1;
+            let line = decode_line(buf.strip_suffix(b"\n").unwrap_or(&buf));
+            let found = if case_sensitive {
+                line.contains(&needle)
+            } else {
+                line.to_lowercase().contains(&needle)
+            };
+            if !found {
+                continue;
+            }
+            if matches.len() == MAX_SEARCH_MATCHES {
+                return Ok(LogSearchResult {
+                    matches,
+                    truncated: true,
+                });
+            }
+            matches.push(LogMatch {
+                filename: filename.clone(),
+                line_number,
+                line,
+            });
+        }
+    }
+
+    Ok(LogSearchResult {
+        matches,
+        truncated: false,
+    })
+}
+
+/// Tails a log file like `tail -f`, emitting each appended line on `log-line-<id>`. Replaces
+/// any watch already running for the project.
+#[command]
+pub fn watch_log_file(
+    id: String,
+    filename: String,
+    state: State<Arc<AppState>>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let log_path = log_file_path(&id, &filename)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = state
+        .log_watchers
+        .lock()
+        .unwrap()
+        .insert(id.clone(), stop.clone())
+    {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    // Start from the end: the frontend already has the existing contents from read_log_file
+    let mut offset = fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0);
+    let mut identity = file_identity(&log_path);
+    let event = format!("log-line-{}", id);
+
+    thread::spawn(move || {
+        let mut partial = Vec::new();
+        while !stop.load(Ordering::Relaxed) {
+            thread::sleep(LOG_POLL_INTERVAL);
+
+            let Ok(metadata) = fs::metadata(&log_path) else {
+                continue; // Mid-rotation; the new file shows up on a later poll
+            };
+            // Truncated, or replaced by a new file (daily logs, logrotate): start over
+            let current_identity = file_identity(&log_path);
+            if metadata.len() < offset || current_identity != identity {
+                offset = 0;
+                partial.clear();
+                identity = current_identity;
+            }
+            if metadata.len() == offset {
+                continue;
+            }
+
+            let Ok(mut file) = fs::File::open(&log_path) else {
+                continue;
+            };
+            if file.seek(SeekFrom::Start(offset)).is_err() {
+                continue;
+            }
+            let mut appended = Vec::new();
+            let Ok(read) = file.read_to_end(&mut appended) else {
+                continue;
+            };
+            offset += read as u64;
+            partial.extend_from_slice(&appended);
+
+            // Only emit complete lines; keep a trailing partial line for the next poll
+            while let Some(end) = partial.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = partial.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line).trim_end().to_string();
+                let _ = app_handle.emit(&event, line);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stops the project's log watch. Returns false if none was running.
+#[command]
+pub fn stop_watching_log(id: String, state: State<Arc<AppState>>) -> Result<bool, String> {
+    match state.log_watchers.lock().unwrap().remove(&id) {
+        Some(stop) => {
+            stop.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Identifies the file behind a path, to notice when a log is replaced rather than appended to.
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| m.ino())
+}
+
+/// Identifies the file behind a path, to notice when a log is replaced rather than appended to.
+#[cfg(not(unix))]
+fn file_identity(path: &Path) -> Option<u64> {
+    // No inode numbers; fall back to the creation time
+    let created = fs::metadata(path).ok()?.created().ok()?;
+    let since_epoch = created.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(since_epoch.as_nanos() as u64)
+}
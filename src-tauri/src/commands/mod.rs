@@ -1,6 +1,12 @@
-pub mod database_commands;
+pub mod composer_commands;
 pub mod db_tool_commands;
+pub mod env_commands;
+pub mod git_commands;
 pub mod lang_commands;
+pub mod laravel_commands;
 pub mod log_commands;
+pub mod npm_commands;
+pub mod process_commands;
 pub mod project_commands;
+pub mod quality_commands;
 pub mod terminal_commands;
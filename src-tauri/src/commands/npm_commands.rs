@@ -0,0 +1,105 @@
+use crate::commands::project_commands::run_streamed;
+use crate::utils::project_location;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+/// Looks up a project's folder and reads its package.json.
+fn read_package_json(project_id: &str) -> Result<(PathBuf, serde_json::Value), String> {
+    let location = project_location(project_id)?;
+
+    let package_path = location.join("package.json");
+    if !package_path.exists() {
+        return Err("package.json not found".to_string());
+    }
+    let content = std::fs::read_to_string(&package_path).map_err(|e| e.to_string())?;
+    let json = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse package.json: {}", e))?;
+    Ok((location, json))
+}
+
+fn scripts(package: &serde_json::Value) -> BTreeMap<String, String> {
+    package
+        .get("scripts")
+        .and_then(|s| s.as_object())
+        .map(|scripts| {
+            scripts
+                .iter()
+                .filter_map(|(name, cmd)| Some((name.clone(), cmd.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Picks the package manager from the lockfile in the project folder: pnpm, then yarn, then
+/// npm, then Bun (`bun.lockb`, or `bun.lock` since Bun 1.2). Defaults to npm.
+fn package_manager(location: &Path) -> &'static str {
+    let has = |file: &str| location.join(file).exists();
+    if has("pnpm-lock.yaml") {
+        "pnpm"
+    } else if has("yarn.lock") {
+        "yarn"
+    } else if has("package-lock.json") {
+        "npm"
+    } else if has("bun.lockb") || has("bun.lock") {
+        "bun"
+    } else {
+        "npm"
+    }
+}
+
+/// The package manager `run_npm_script` uses for the project, so the UI can label its
+/// install and run actions.
+#[command]
+pub fn detect_package_manager(project_id: String) -> Result<String, String> {
+    Ok(package_manager(&project_location(&project_id)?).to_string())
+}
+
+/// The `scripts` declared in package.json, by name.
+#[command]
+pub fn get_npm_scripts(project_id: String) -> Result<BTreeMap<String, String>, String> {
+    let (_, package) = read_package_json(&project_id)?;
+    Ok(scripts(&package))
+}
+
+/// Runs a package.json script with the project's package manager, streaming output to
+/// `npm-output-<project_id>`. Returns the exit code.
+#[command(async)]
+pub fn run_npm_script(
+    project_id: String,
+    script: String,
+    app_handle: AppHandle,
+) -> Result<i32, String> {
+    let (location, package) = read_package_json(&project_id)?;
+    if !scripts(&package).contains_key(&script) {
+        return Err(format!(
+            "Script '{}' is not defined in package.json",
+            script
+        ));
+    }
+
+    let manager = package_manager(&location);
+    // npm, pnpm and yarn are .cmd shims on Windows, which Command won't find by bare name;
+    // Bun ships a real executable
+    let program = if cfg!(windows) && manager != "bun" {
+        format!("{}.cmd", manager)
+    } else {
+        manager.to_string()
+    };
+    let mut cmd = std::process::Command::new(program);
+    cmd.current_dir(&location).arg("run").arg(&script);
+
+    run_streamed(
+        cmd,
+        &project_id,
+        format!("{} run {}", manager, script),
+        &format!("npm-output-{}", project_id),
+        &app_handle,
+        |e| {
+            format!(
+                "Failed to execute {}: {}. Make sure it is installed and in your PATH.",
+                manager, e
+            )
+        },
+    )
+}
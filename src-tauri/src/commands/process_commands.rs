@@ -0,0 +1,187 @@
+use crate::commands::project_commands::{configure_command_env, forward_lines};
+use crate::state::{AppState, TrackedProcess};
+use crate::utils::project_location;
+use chrono::Utc;
+use serde::Serialize;
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use tauri::{command, AppHandle, State};
+
+const DEFAULT_DEV_SERVER_PORT: u16 = 8000;
+const DEV_SERVER_LABEL: &str = "serve";
+
+#[derive(Debug, Serialize)]
+pub struct ProcessInfo {
+    pub label: String,
+    pub pid: u32,
+    pub running: bool,
+    /// Exit code once the process has stopped; None while running or if killed by a signal
+    pub exit_code: Option<i32>,
+    pub port: Option<u16>,
+    pub started_at: String,
+}
+
+/// Puts the process in its own process group (unix) so it can be stopped together with
+/// anything it spawns.
+pub(crate) fn isolate_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Kills a process and its children. `php artisan serve` runs the actual server as a child
+/// process, so killing only the parent would leave the port bound.
+pub(crate) fn kill_process_tree(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        // The child leads its own process group, see `isolate_process_group`
+        libc::killpg(child.id() as i32, libc::SIGTERM);
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .output();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Starts `php artisan serve` for a project, streaming its output to
+/// `dev-server-output-<project_id>`. Returns the port it listens on.
+#[command]
+pub fn start_dev_server(
+    project_id: String,
+    port: Option<u16>,
+    state: State<Arc<AppState>>,
+    app_handle: AppHandle,
+) -> Result<u16, String> {
+    let port = port.unwrap_or(DEFAULT_DEV_SERVER_PORT);
+
+    let location = project_location(&project_id)?;
+    if !location.join("artisan").exists() {
+        return Err("Artisan not found".to_string());
+    }
+
+    let mut cmd = Command::new("php");
+    cmd.current_dir(&location)
+        .arg("artisan")
+        .arg("serve")
+        .arg(format!("--port={}", port))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    configure_command_env(&mut cmd);
+    isolate_process_group(&mut cmd);
+
+    // Checked and registered under one lock so two quick clicks can't start two servers
+    let (stdout, stderr) = state.processes.with_project(&project_id, |tracked| {
+        if let Some(running) = dev_server_port(tracked) {
+            return Err(format!(
+                "A dev server is already running on port {}",
+                running
+            ));
+        }
+        if TcpListener::bind(("127.0.0.1", port)).is_err() {
+            return Err(format!("Port {} is already in use", port));
+        }
+
+        let mut child = cmd.spawn().map_err(|e| {
+            format!(
+                "Failed to execute php artisan: {}. Make sure PHP is installed and in your PATH.",
+                e
+            )
+        })?;
+        let output = (child.stdout.take(), child.stderr.take());
+        // Replaces a previous server that has exited
+        tracked.retain(|p| p.label != DEV_SERVER_LABEL);
+        tracked.push(TrackedProcess {
+            label: DEV_SERVER_LABEL.to_string(),
+            child,
+            port: Some(port),
+            started_at: Utc::now().to_rfc3339(),
+        });
+        Ok(output)
+    })?;
+
+    let event = format!("dev-server-output-{}", project_id);
+    if let Some(stdout) = stdout {
+        forward_lines(stdout, "stdout", &event, &app_handle);
+    }
+    if let Some(stderr) = stderr {
+        forward_lines(stderr, "stderr", &event, &app_handle);
+    }
+    Ok(port)
+}
+
+/// Stops the project's dev server. Returns false if none was running.
+#[command]
+pub fn stop_dev_server(project_id: String, state: State<Arc<AppState>>) -> Result<bool, String> {
+    kill_process(project_id, DEV_SERVER_LABEL.to_string(), state)
+}
+
+/// Port of the project's running dev server, for opening it in the browser.
+#[command]
+pub fn get_dev_server_port(
+    project_id: String,
+    state: State<Arc<AppState>>,
+) -> Result<Option<u16>, String> {
+    Ok(state
+        .processes
+        .with_project(&project_id, |tracked| dev_server_port(tracked)))
+}
+
+/// Port of the project's dev server, if it's still running.
+fn dev_server_port(tracked: &mut [TrackedProcess]) -> Option<u16> {
+    let server = tracked.iter_mut().find(|p| p.label == DEV_SERVER_LABEL)?;
+    if matches!(server.child.try_wait(), Ok(None)) {
+        server.port
+    } else {
+        None
+    }
+}
+
+/// Processes the app started for a project, including ones that have since exited.
+#[command]
+pub fn list_running_processes(
+    project_id: String,
+    state: State<Arc<AppState>>,
+) -> Result<Vec<ProcessInfo>, String> {
+    Ok(state.processes.with_project(&project_id, |tracked| {
+        tracked
+            .iter_mut()
+            .map(|process| {
+                let exit_status = process.child.try_wait().ok().flatten();
+                ProcessInfo {
+                    label: process.label.clone(),
+                    pid: process.child.id(),
+                    running: exit_status.is_none(),
+                    exit_code: exit_status.and_then(|status| status.code()),
+                    port: process.port,
+                    started_at: process.started_at.clone(),
+                }
+            })
+            .collect()
+    }))
+}
+
+/// Stops one of the project's processes and forgets it. Returns false if there was no
+/// process with that label.
+#[command]
+pub fn kill_process(
+    project_id: String,
+    label: String,
+    state: State<Arc<AppState>>,
+) -> Result<bool, String> {
+    match state.processes.remove(&project_id, &label) {
+        Some(mut process) => {
+            kill_process_tree(&mut process.child);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -1,44 +1,60 @@
+use crate::commands::db_tool_commands::delete_saved_passwords;
+use crate::commands::process_commands::isolate_process_group;
 use crate::database::Database;
-use crate::models::project::{Project, ProjectStatus};
-use crate::state::AppState;
-use crate::utils::get_db_path;
+use crate::error::{CommandError, CommandResult};
+use crate::models::project::{GetProjectsParams, Project, ProjectPage, ProjectStatus};
+use crate::state::{AppState, TrackedProcess};
+use crate::utils::{get_db_path, project_location};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::command;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-/// Helper function to configure a Command with proper environment variables
-/// This ensures that PHP, composer, and other system commands are accessible
-fn configure_command_env(cmd: &mut std::process::Command) {
-    // Get the user's actual PATH by running a login shell
-    // This is necessary because when the app is launched from Finder,
-    // it doesn't have the user's PATH from .zshrc, .bash_profile, etc.
+/// PATH reported by the user's login shell. Spawning a login shell is slow, so it's looked
+/// up once and reused; `refresh_env` clears it after the user edits their profile.
+/// The outer Option is "not looked up yet", the inner one "lookup failed".
+static LOGIN_SHELL_PATH: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+fn login_shell_path() -> Option<String> {
+    let mut cached = LOGIN_SHELL_PATH.lock().unwrap();
+    cached.get_or_insert_with(read_login_shell_path).clone()
+}
+
+/// Get the user's actual PATH by running a login shell
+/// This is necessary because when the app is launched from Finder,
+/// it doesn't have the user's PATH from .zshrc, .bash_profile, etc.
+fn read_login_shell_path() -> Option<String> {
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
 
-    // Try to get the PATH from a login shell
-    if let Ok(output) = std::process::Command::new(&shell)
+    let output = std::process::Command::new(&shell)
         .arg("-l")
         .arg("-c")
         .arg("echo $PATH")
         .output()
-    {
-        if output.status.success() {
-            if let Ok(path) = String::from_utf8(output.stdout) {
-                let path = path.trim();
-                if !path.is_empty() {
-                    cmd.env("PATH", path);
-                }
-            }
-        }
+        .ok()?;
+    if !output.status.success() {
+        return None;
     }
 
-    // Fallback: try to inherit PATH from current process
-    if let Ok(path) = std::env::var("PATH") {
-        // Only set if we didn't already set it from the shell
-        if cmd.get_envs().all(|(k, _)| k != "PATH") {
-            cmd.env("PATH", path);
-        }
+    let path = String::from_utf8(output.stdout).ok()?;
+    let path = path.trim();
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}
+
+/// Helper function to configure a Command with proper environment variables
+/// This ensures that PHP, composer, and other system commands are accessible
+pub(crate) fn configure_command_env(cmd: &mut std::process::Command) {
+    // Prefer the login shell's PATH, falling back to the one inherited by this process
+    if let Some(path) = login_shell_path().or_else(|| std::env::var("PATH").ok()) {
+        cmd.env("PATH", path);
     }
 
     // Also inherit HOME for proper shell initialization
@@ -52,6 +68,172 @@ fn configure_command_env(cmd: &mut std::process::Command) {
     }
 }
 
+/// One line of output from a streamed command.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputLine {
+    pub stream: &'static str, // "stdout" or "stderr"
+    pub line: String,
+}
+
+/// How often `run_streamed` checks whether its process has exited.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs `cmd`, emitting each line of stdout and stderr on `event` as it arrives, and returns
+/// the exit code (-1 if the process was killed). While it runs the process is tracked under
+/// `label` in the project's `ProcessRegistry`, so it shows up in `list_running_processes`,
+/// can be stopped with `kill_process` and is killed when the app exits. Fails if a process
+/// with the same label is still running. `spawn_error` turns a launch failure into a
+/// user-facing message.
+pub(crate) fn run_streamed(
+    cmd: std::process::Command,
+    project_id: &str,
+    label: String,
+    event: &str,
+    app_handle: &AppHandle,
+    spawn_error: impl FnOnce(std::io::Error) -> String,
+) -> Result<i32, String> {
+    run_tracked(
+        cmd,
+        project_id,
+        label,
+        event,
+        app_handle,
+        false,
+        spawn_error,
+    )
+    .map(|(exit_code, _)| exit_code)
+}
+
+/// Like `run_streamed`, but collects stdout and returns it with the exit code instead of
+/// emitting it; only stderr is streamed. For tools that print a report on stdout.
+pub(crate) fn run_captured(
+    cmd: std::process::Command,
+    project_id: &str,
+    label: String,
+    event: &str,
+    app_handle: &AppHandle,
+    spawn_error: impl FnOnce(std::io::Error) -> String,
+) -> Result<(i32, String), String> {
+    run_tracked(cmd, project_id, label, event, app_handle, true, spawn_error)
+}
+
+fn run_tracked(
+    mut cmd: std::process::Command,
+    project_id: &str,
+    label: String,
+    event: &str,
+    app_handle: &AppHandle,
+    capture_stdout: bool,
+    spawn_error: impl FnOnce(std::io::Error) -> String,
+) -> Result<(i32, String), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    configure_command_env(&mut cmd);
+    isolate_process_group(&mut cmd);
+    cmd.stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    // Checked and registered under one lock so the same command can't start twice
+    let (pid, stdout, stderr) = state.processes.with_project(project_id, |tracked| {
+        let running = tracked
+            .iter_mut()
+            .any(|p| p.label == label && matches!(p.child.try_wait(), Ok(None)));
+        if running {
+            return Err("This command is already running".to_string());
+        }
+        tracked.retain(|p| p.label != label);
+
+        let mut child = cmd.spawn().map_err(spawn_error)?;
+        let output = (child.id(), child.stdout.take(), child.stderr.take());
+        tracked.push(TrackedProcess {
+            label: label.clone(),
+            child,
+            port: None,
+            started_at: Utc::now().to_rfc3339(),
+        });
+        Ok(output)
+    })?;
+
+    let mut readers = Vec::new();
+    let mut captured = None;
+    match stdout {
+        Some(mut stdout) if capture_stdout => {
+            captured = Some(std::thread::spawn(move || {
+                let mut output = Vec::new();
+                let _ = stdout.read_to_end(&mut output);
+                String::from_utf8_lossy(&output).into_owned()
+            }));
+        }
+        Some(stdout) => readers.push(forward_lines(stdout, "stdout", event, app_handle)),
+        None => {}
+    }
+    if let Some(stderr) = stderr {
+        readers.push(forward_lines(stderr, "stderr", event, app_handle));
+    }
+
+    // The registry owns the child, so poll it rather than blocking in wait() with the lock
+    // held. It disappears from the registry when kill_process or app shutdown stops it; the
+    // pid tells it apart from a rerun under the same label.
+    let status = loop {
+        let finished = state.processes.with_project(project_id, |tracked| {
+            let index = tracked
+                .iter()
+                .position(|p| p.label == label && p.child.id() == pid);
+            let Some(index) = index else {
+                return Some(None);
+            };
+            match tracked[index].child.try_wait() {
+                Ok(None) => None,
+                result => {
+                    tracked.remove(index);
+                    Some(result.ok().flatten())
+                }
+            }
+        });
+        if let Some(status) = finished {
+            break status;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    };
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let stdout = captured
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+    Ok((
+        status.and_then(|status| status.code()).unwrap_or(-1),
+        stdout,
+    ))
+}
+
+/// Emits each line read from `reader` on `event` from a background thread.
+pub(crate) fn forward_lines(
+    reader: impl Read + Send + 'static,
+    stream: &'static str,
+    event: &str,
+    app_handle: &AppHandle,
+) -> std::thread::JoinHandle<()> {
+    let app_handle = app_handle.clone();
+    let event = event.to_string();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+            let _ = app_handle.emit(&event, OutputLine { stream, line });
+            buf.clear();
+        }
+    })
+}
+
+/// Re-reads the login shell's PATH after the user changes their shell profile.
+#[command]
+pub fn refresh_env() -> Option<String> {
+    let path = read_login_shell_path();
+    *LOGIN_SHELL_PATH.lock().unwrap() = Some(path.clone());
+    path
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LaravelCommand {
     pub name: String,
@@ -59,18 +241,19 @@ pub struct LaravelCommand {
 }
 
 #[command]
+#[tracing::instrument(skip(state), err)]
 pub fn create_project(
     name: String,
     description: String,
     location: String,
     status: ProjectStatus,
     state: State<Arc<AppState>>,
-) -> Result<Project, String> {
+) -> CommandResult<Project> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
+    let db = Database::new(db_path)?;
     let project = Project::new(name, description, location, status);
 
-    db.create_project(&project).map_err(|e| e.to_string())?;
+    db.create_project(&project)?;
 
     // Emit event to channel
     let _ = state
@@ -82,30 +265,119 @@ pub fn create_project(
     Ok(project)
 }
 
+/// Copies a project's metadata into a new project. When `new_location` is given the source
+/// folder is copied there too; otherwise the copy points at the same folder. The database
+/// configuration is not carried over.
 #[command]
-pub fn get_projects() -> Result<Vec<Project>, String> {
+#[tracing::instrument(skip(state), err)]
+pub fn duplicate_project(
+    id: String,
+    new_name: String,
+    new_location: Option<String>,
+    state: State<Arc<AppState>>,
+) -> CommandResult<Project> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    db.get_projects().map_err(|e| e.to_string())
+    let db = Database::new(db_path)?;
+    let source = db
+        .get_project_by_id(&id)?
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
+
+    let location = match new_location {
+        Some(new_location) => {
+            let target = Path::new(&new_location);
+            let occupied = target
+                .read_dir()
+                .map(|mut entries| entries.next().is_some())
+                .unwrap_or(target.exists());
+            if occupied {
+                return Err(CommandError::InvalidInput(format!(
+                    "{} already exists and is not empty",
+                    new_location
+                )));
+            }
+            if target.starts_with(&source.location) {
+                return Err(CommandError::InvalidInput(
+                    "Cannot copy a project into its own folder".to_string(),
+                ));
+            }
+            copy_dir_all(Path::new(&source.location), target)
+                .map_err(|e| CommandError::Io(format!("Failed to copy project folder: {}", e)))?;
+            new_location
+        }
+        None => source.location.clone(),
+    };
+
+    let project = Project::new(new_name, source.description, location, source.status);
+    db.create_project(&project)?;
+
+    // Runs setup_project on the copy, same as for a new project
+    let _ = state
+        .project_event_tx
+        .lock()
+        .unwrap()
+        .send(project.id.clone());
+
+    Ok(project)
+}
+
+fn copy_dir_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    // Creating symlinks needs extra privileges on Windows, so copy the target instead
+    if from.is_dir() {
+        copy_dir_all(from, to)
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
 }
 
 #[command]
+#[tracing::instrument(err)]
+pub fn get_projects(params: Option<GetProjectsParams>) -> CommandResult<ProjectPage> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+    Ok(db.query_projects(&params.unwrap_or_default())?)
+}
+
+#[command]
+#[tracing::instrument(err)]
 pub fn update_project(
     id: String,
     name: Option<String>,
     description: Option<String>,
     location: Option<String>,
     status: Option<ProjectStatus>,
-) -> Result<Project, String> {
+) -> CommandResult<Project> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
+    let db = Database::new(db_path)?;
 
     // First, get the existing project
-    let mut existing_projects = db.get_projects().map_err(|e| e.to_string())?;
+    let mut existing_projects = db.get_projects()?;
     let existing_project = existing_projects
         .iter_mut()
         .find(|p| p.id == id)
-        .ok_or("Project not found".to_string())?;
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
 
     // Update fields if provided
     if let Some(new_name) = name {
@@ -124,46 +396,87 @@ pub fn update_project(
     // Update timestamp
     existing_project.updated_at = Utc::now().to_rfc3339();
 
-    db.update_project(&id, existing_project)
-        .map_err(|e| e.to_string())?;
+    db.update_project(&id, existing_project)?;
     Ok(existing_project.clone())
 }
 
+/// Pins or unpins a project at the top of the list. Returns whether it is now a favorite.
+#[command]
+#[tracing::instrument(err)]
+pub fn toggle_favorite(id: String) -> CommandResult<bool> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+    db.toggle_favorite(&id)?
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))
+}
+
+/// Moves a project to the recycle bin; its folder and `.workshop` config are left as-is.
+#[command]
+#[tracing::instrument(err)]
+pub fn delete_project(id: String) -> CommandResult<bool> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+    Ok(db.delete_project(&id)?)
+}
+
 #[command]
-pub fn delete_project(id: String) -> Result<bool, String> {
+#[tracing::instrument(err)]
+pub fn restore_project(id: String) -> CommandResult<bool> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    db.delete_project(&id).map_err(|e| e.to_string())
+    let db = Database::new(db_path)?;
+    Ok(db.restore_project(&id)?)
+}
+
+#[command]
+#[tracing::instrument(err)]
+pub fn list_deleted_projects() -> CommandResult<Vec<Project>> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+    Ok(db.get_deleted_projects()?)
+}
+
+/// Permanently removes a project from the recycle bin, along with the database passwords
+/// kept for it in the OS keychain.
+#[command]
+#[tracing::instrument(err)]
+pub fn purge_project(id: String) -> CommandResult<bool> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+    // Only binned projects can be purged, so leave a live project's passwords alone
+    if let Some(project) = db
+        .get_project_by_id(&id)?
+        .filter(|project| project.deleted_at.is_some())
+    {
+        delete_saved_passwords(&project);
+    }
+    Ok(db.purge_project(&id)?)
 }
 
 #[command]
-pub fn open_folder(location: String) -> Result<(), String> {
+#[tracing::instrument(err)]
+pub fn open_folder(location: String) -> CommandResult<()> {
     // Use std::process::Command to launch the editor
     if cfg!(windows) {
         std::process::Command::new("explorer")
             .arg(location)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+            .spawn()?;
         return Ok(());
     } else if cfg!(target_os = "macos") {
-        std::process::Command::new("open")
-            .arg(location)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+        std::process::Command::new("open").arg(location).spawn()?;
         return Ok(());
     } else if cfg!(unix) {
         std::process::Command::new("xdg-open")
             .arg(location)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+            .spawn()?;
         return Ok(());
     } else {
-        return Err("Unsupported OS".to_string());
+        return Err(CommandError::Unsupported("Unsupported OS".to_string()));
     }
 }
 
 #[command]
-pub fn open_in_editor(editor: String, location: String, line: Option<u32>) -> Result<(), String> {
+#[tracing::instrument(err)]
+pub fn open_in_editor(editor: String, location: String, line: Option<u32>) -> CommandResult<()> {
     // Map display names to actual commands
     let command = match editor.as_str() {
         "VSCode" | "code" => "code",
@@ -171,7 +484,12 @@ pub fn open_in_editor(editor: String, location: String, line: Option<u32>) -> Re
         "PHPStorm" | "phpstorm" => "phpstorm",
         "Windsurf" | "windsurf" => "windsurf",
         "Zed" | "zed" => "zed",
-        _ => return Err(format!("Unsupported editor: {}", editor)),
+        _ => {
+            return Err(CommandError::Unsupported(format!(
+                "Unsupported editor: {}",
+                editor
+            )))
+        }
     };
 
     // Use std::process::Command to launch the editor
@@ -200,123 +518,270 @@ pub fn open_in_editor(editor: String, location: String, line: Option<u32>) -> Re
     // Configure environment to ensure editor commands are accessible
     configure_command_env(&mut cmd);
 
-    cmd.spawn().map_err(|e| e.to_string())?;
+    cmd.spawn()?;
 
     Ok(())
 }
 
 #[command]
-pub fn get_project_type(id: String) -> Result<String, String> {
+#[tracing::instrument(err)]
+pub fn get_project_type(id: String) -> CommandResult<String> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
+    let db = Database::new(db_path)?;
 
     // First, get the existing project
-    let mut existing_projects = db.get_projects().map_err(|e| e.to_string())?;
+    let mut existing_projects = db.get_projects()?;
     let existing_project = existing_projects
         .iter_mut()
         .find(|p| p.id == id)
-        .ok_or("Project not found".to_string())?;
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
 
-    // Get the location of the project
-    let location = &existing_project.location;
+    Ok(detect_project_type(&existing_project.location)?)
+}
 
+/// Composer packages that identify a framework, checked in order against `require`.
+/// Add a row here to recognise another framework.
+const COMPOSER_FRAMEWORKS: &[(&str, &str)] = &[
+    ("laravel/framework", "Laravel"),
+    ("symfony/framework-bundle", "Symfony"),
+];
+
+/// npm packages that identify a JavaScript framework, checked in order against
+/// `dependencies` and `devDependencies`. Meta-frameworks come before the libraries they
+/// build on (Next.js pulls in React), so the most specific match wins. A `package.json`
+/// matching none of these is a plain "Node" project.
+const NODE_FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("vue", "Vue"),
+    ("react", "React"),
+    ("express", "Express"),
+];
+
+/// Works out a project's framework from the files in its folder.
+fn detect_project_type(location: &str) -> Result<String, String> {
     // try getting the file `composer.json` from the project location
     let composer_path = format!("{}/composer.json", location);
     if std::path::Path::new(&composer_path).exists() {
         // If the file exists, read the file to determine the project type
         let content = std::fs::read_to_string(&composer_path).map_err(|e| e.to_string())?;
         let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-        // Check if the file has `laravel/framework` in the dependencies
         if let Some(dependencies) = json.get("require").and_then(|r| r.as_object()) {
-            if dependencies.contains_key("laravel/framework") {
-                // The project is a Laravel project. Send as response
-
-                return Ok("Laravel".to_string());
+            for (package, project_type) in COMPOSER_FRAMEWORKS {
+                if dependencies.contains_key(*package) {
+                    return Ok(project_type.to_string());
+                }
             }
         }
     }
 
-    // Placeholder: return Ok until implementation is complete
-    return Ok("Unknown".to_string());
+    // WordPress sites often ship a composer.json for plugins or a package.json for theme
+    // builds, so check for it after the composer frameworks but before Node
+    if ["wp-config.php", "wp-config-sample.php"]
+        .iter()
+        .any(|file| std::path::Path::new(location).join(file).exists())
+    {
+        return Ok("WordPress".to_string());
+    }
+
+    // PHP projects take precedence: a Laravel app with a Vue frontend is still Laravel
+    let package_path = format!("{}/package.json", location);
+    if std::path::Path::new(&package_path).exists() {
+        let content = std::fs::read_to_string(&package_path).map_err(|e| e.to_string())?;
+        let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let has_dependency = |package: &str| {
+            ["dependencies", "devDependencies"].iter().any(|section| {
+                json.get(section)
+                    .and_then(|d| d.as_object())
+                    .is_some_and(|d| d.contains_key(package))
+            })
+        };
+        let project_type = NODE_FRAMEWORKS
+            .iter()
+            .find(|(package, _)| has_dependency(package))
+            .map_or("Node", |(_, project_type)| project_type);
+        return Ok(project_type.to_string());
+    }
+
+    Ok("Unknown".to_string())
 }
 
+/// Files or folders whose presence marks a directory as a project when importing.
+const PROJECT_MARKERS: &[&str] = &["composer.json", "package.json", ".git"];
+
+/// Adds every immediate subdirectory of `parent_dir` that looks like a project and isn't
+/// tracked yet. Returns the projects that were created.
 #[command]
-pub fn setup_project(id: String, _state: std::sync::Arc<AppState>) -> Result<String, String> {
+#[tracing::instrument(skip(state), err)]
+pub fn import_projects_from_dir(
+    parent_dir: String,
+    state: State<Arc<AppState>>,
+) -> CommandResult<Vec<Project>> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
-    // Get the project
-    let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
-    let location = &project.location;
+    let db = Database::new(db_path)?;
+
+    // Projects in the recycle bin count as tracked; restoring them is the way back
+    let mut known = db.get_projects()?;
+    known.extend(db.get_deleted_projects()?);
+
+    let mut dirs: Vec<_> = std::fs::read_dir(&parent_dir)
+        .map_err(|e| CommandError::Io(format!("Failed to read {}: {}", parent_dir, e)))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    dirs.sort();
+
+    let mut imported = Vec::new();
+    for dir in dirs {
+        if !PROJECT_MARKERS
+            .iter()
+            .any(|marker| dir.join(marker).exists())
+        {
+            continue;
+        }
+        if known.iter().any(|p| Path::new(&p.location) == dir) {
+            continue;
+        }
+
+        let location = dir.to_string_lossy().to_string();
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| location.clone());
+        // A malformed composer.json shouldn't stop the rest of the import
+        let project_type = detect_project_type(&location).unwrap_or_else(|_| "Unknown".to_string());
+        let description = if project_type == "Unknown" {
+            String::new()
+        } else {
+            format!("{} project", project_type)
+        };
+
+        let project = Project::new(name, description, location, ProjectStatus::InProgress);
+        db.create_project(&project)?;
+        let _ = state
+            .project_event_tx
+            .lock()
+            .unwrap()
+            .send(project.id.clone());
+        imported.push(project);
+    }
+
+    Ok(imported)
+}
+
+/// Prepares a new project's `.workshop` folder. Reports each step on
+/// `project-setup-progress-<id>`, then emits `project-setup-complete` with the detected type
+/// or `project-setup-error` if a step failed.
+#[command]
+#[tracing::instrument(skip(app_handle), err)]
+pub fn setup_project(id: String, app_handle: AppHandle) -> CommandResult<String> {
+    let result = run_project_setup(&id, &app_handle);
+    let _ = match &result {
+        Ok(project_type) => app_handle.emit(
+            "project-setup-complete",
+            serde_json::json!({ "id": id, "project_type": project_type }),
+        ),
+        Err(error) => app_handle.emit(
+            "project-setup-error",
+            serde_json::json!({ "id": id, "error": error }),
+        ),
+    };
+    Ok(result?)
+}
+
+fn run_project_setup(id: &str, app_handle: &AppHandle) -> Result<String, String> {
+    let progress_event = format!("project-setup-progress-{}", id);
+    let progress = |step: &str| {
+        let _ = app_handle.emit(&progress_event, step);
+    };
+
+    let location = project_location(id)?.to_string_lossy().into_owned();
 
     // Check/create .workshop folder
+    progress("creating_workshop_dir");
     let workshop_dir = format!("{}/.workshop", location);
     if !std::path::Path::new(&workshop_dir).exists() {
         std::fs::create_dir_all(&workshop_dir).map_err(|e| e.to_string())?;
     }
 
     // Get project type
-    let project_type = get_project_type(id)?;
+    progress("detecting_type");
+    let project_type = detect_project_type(&location)?;
 
-    // Write project.json only if it doesn't exist
+    // Store the type unless project.json already has a known one; other keys are kept
+    progress("writing_config");
     let json_path = format!("{}/project.json", workshop_dir);
-    if !std::path::Path::new(&json_path).exists() {
-        let json_content = serde_json::json!({ "project_type": project_type });
-        std::fs::write(
-            &json_path,
-            serde_json::to_string_pretty(&json_content).map_err(|e| e.to_string())?,
-        )
-        .map_err(|e| e.to_string())?;
+    let mut config = read_project_json(&json_path)?;
+    let stored = config.get("project_type").and_then(|v| v.as_str());
+    if stored.is_none() || stored == Some("Unknown") {
+        config.insert(
+            "project_type".to_string(),
+            serde_json::Value::String(project_type.clone()),
+        );
+        write_project_json(&json_path, &config)?;
     }
 
     Ok(project_type)
 }
 
+/// Reads `.workshop/project.json` as an object, or an empty one if the file doesn't exist.
+pub(crate) fn read_project_json(
+    path: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if !std::path::Path::new(path).exists() {
+        return Ok(serde_json::Map::new());
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    match serde_json::from_str(&content).map_err(|e| e.to_string())? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err(format!("{} is not a JSON object", path)),
+    }
+}
+
+fn write_project_json(
+    path: &str,
+    config: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
 #[command]
-pub fn get_project_config(id: String, key: String) -> Result<Option<String>, String> {
+#[tracing::instrument(err)]
+pub fn get_project_config(id: String, key: String) -> CommandResult<Option<String>> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
+    let db = Database::new(db_path)?;
 
     // Get the project location
     let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
+        .get_project_by_id(&id)?
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
 
-    // Check if project.json exists
+    // Read project.json, if there is one
     let config_path = format!("{}/.workshop/project.json", project.location);
-    if !std::path::Path::new(&config_path).exists() {
-        // Fallback for project_type if file doesn't exist
-        if key == "project_type" {
-            if let Ok(project_type) = get_project_type(id) {
-                if project_type != "Unknown" {
-                    return Ok(Some(project_type));
-                }
-            }
-        }
-        return Ok(None);
-    }
-
-    // Read and parse the JSON file
-    let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let mut config = read_project_json(&config_path)?;
 
     // Get the value for the requested key
-    if let Some(value) = json.get(&key) {
-        if let Some(str_value) = value.as_str() {
-            return Ok(Some(str_value.to_string()));
-        }
+    if let Some(str_value) = config.get(&key).and_then(|v| v.as_str()) {
+        return Ok(Some(str_value.to_string()));
     }
 
-    // Fallback for project_type if not found in config
+    // Fallback for project_type if not found in config: detect it and save it so the next
+    // lookup doesn't have to
     if key == "project_type" {
-        if let Ok(project_type) = get_project_type(id.clone()) {
-            // Optionally save it back to project.json? For now just return it.
-            // To save it, we would need to update the json and write it back.
-            // Let's just return it to be safe and fast.
+        if let Ok(project_type) = detect_project_type(&project.location) {
             if project_type != "Unknown" {
+                config.insert(key, serde_json::Value::String(project_type.clone()));
+                let saved = std::fs::create_dir_all(format!("{}/.workshop", project.location))
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| write_project_json(&config_path, &config));
+                if let Err(e) = saved {
+                    tracing::warn!(path = %config_path, error = %e, "Failed to save project type");
+                }
                 return Ok(Some(project_type));
             }
         }
@@ -325,22 +790,48 @@ pub fn get_project_config(id: String, key: String) -> Result<Option<String>, Str
     Ok(None)
 }
 
+/// Sets a key in `.workshop/project.json`, creating the file if needed. A `null` value
+/// removes the key.
+#[command]
+#[tracing::instrument(err)]
+pub fn set_project_config(id: String, key: String, value: serde_json::Value) -> CommandResult<()> {
+    let db_path = get_db_path()?;
+    let db = Database::new(db_path)?;
+
+    // Get the project location
+    let project = db
+        .get_project_by_id(&id)?
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
+
+    let workshop_dir = format!("{}/.workshop", project.location);
+    std::fs::create_dir_all(&workshop_dir)?;
+
+    let config_path = format!("{}/project.json", workshop_dir);
+    let mut config = read_project_json(&config_path)?;
+    if value.is_null() {
+        config.remove(&key);
+    } else {
+        config.insert(key, value);
+    }
+    Ok(write_project_json(&config_path, &config)?)
+}
+
 #[command]
-pub fn get_laravel_commands(id: String) -> Result<Vec<LaravelCommand>, String> {
+#[tracing::instrument(err)]
+pub fn get_laravel_commands(id: String) -> CommandResult<Vec<LaravelCommand>> {
     let db_path = get_db_path()?;
-    let db = Database::new(db_path).map_err(|e| e.to_string())?;
+    let db = Database::new(db_path)?;
 
     // Get the project location
     let project = db
-        .get_project_by_id(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Project not found".to_string())?;
+        .get_project_by_id(&id)?
+        .ok_or_else(|| CommandError::NotFound("Project not found".to_string()))?;
 
     let location = project.location;
     let artisan_path = format!("{}/artisan", location);
 
     if !std::path::Path::new(&artisan_path).exists() {
-        return Err("Artisan not found".to_string());
+        return Err(CommandError::NotFound("Artisan not found".to_string()));
     }
 
     // Run php artisan list --format=json from the project directory
@@ -361,7 +852,9 @@ pub fn get_laravel_commands(id: String) -> Result<Vec<LaravelCommand>, String> {
     })?;
 
     if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        return Err(CommandError::Other(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
     }
 
     let content = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
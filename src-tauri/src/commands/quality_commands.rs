@@ -0,0 +1,731 @@
+use crate::commands::project_commands::{run_captured, run_streamed};
+use crate::utils::project_location;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{command, AppHandle};
+
+#[derive(Debug, Default, Serialize)]
+pub struct TestSummary {
+    pub runner: String, // "pest" or "phpunit"
+    pub exit_code: i32,
+    pub tests: u32,
+    pub passed: u32,
+    pub failed: u32,  // Failures and errors
+    pub skipped: u32, // Also incomplete and risky tests, which JUnit reports as skipped
+    pub time: f64,    // Seconds
+    pub failures: Vec<TestFailure>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestFailure {
+    pub name: String,
+    pub class: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub kind: String, // "failure" for assertions, "error" for exceptions
+    pub message: String,
+}
+
+/// Files Pint reformatted, or would reformat when run with `fix: false`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PintResult {
+    pub files: Vec<PintFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PintFile {
+    #[serde(alias = "name")]
+    pub path: String, // Relative to the project folder
+    #[serde(default, alias = "appliedFixers")]
+    pub fixers: Vec<String>, // Rules that changed the file, e.g. "no_unused_imports"
+}
+
+/// Whether composer.json requires `package`, in either `require` or `require-dev`.
+fn composer_requires(location: &Path, package: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(location.join("composer.json")) else {
+        return false;
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return false;
+    };
+    ["require", "require-dev"]
+        .iter()
+        .any(|section| json[section].get(package).is_some())
+}
+
+/// Picks Pest when it is installed, otherwise PHPUnit. A runner listed in composer.json but
+/// missing from vendor/bin means dependencies haven't been installed yet.
+fn detect_test_runner(location: &Path) -> Result<&'static str, String> {
+    let root = location;
+    let installed = |runner: &str| root.join("vendor").join("bin").join(runner).exists();
+    if installed("pest") {
+        return Ok("pest");
+    }
+    let configured = ["phpunit.xml", "phpunit.xml.dist"]
+        .iter()
+        .any(|config| root.join(config).exists());
+    if installed("phpunit") && (configured || composer_requires(location, "phpunit/phpunit")) {
+        return Ok("phpunit");
+    }
+
+    if composer_requires(location, "pestphp/pest") || composer_requires(location, "phpunit/phpunit")
+    {
+        return Err(
+            "The test runner is in composer.json but not installed. Run `composer install` first."
+                .to_string(),
+        );
+    }
+    Err("No test runner configured. Add PHPUnit or Pest to the project with Composer.".to_string())
+}
+
+/// Runs the project's PHPUnit or Pest suite, streaming output to `test-output-<project_id>`,
+/// and summarizes the JUnit report it writes.
+#[command(async)]
+pub fn run_tests(project_id: String, app_handle: AppHandle) -> Result<TestSummary, String> {
+    let location = project_location(&project_id)?;
+    let runner = detect_test_runner(&location)?;
+
+    let report = std::env::temp_dir().join(format!("workshop-junit-{}.xml", uuid::Uuid::new_v4()));
+    // Composer's vendor/bin proxies are PHP scripts, so run them through php on every platform
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(&location)
+        .arg(Path::new("vendor").join("bin").join(runner))
+        .arg("--log-junit")
+        .arg(&report)
+        .arg("--colors=never");
+
+    let exit_code = run_streamed(
+        cmd,
+        &project_id,
+        "tests".to_string(),
+        &format!("test-output-{}", project_id),
+        &app_handle,
+        |e| {
+            format!(
+                "Failed to run {}: {}. Make sure PHP is installed and in your PATH.",
+                runner, e
+            )
+        },
+    )?;
+
+    let xml = std::fs::read_to_string(&report);
+    let _ = std::fs::remove_file(&report);
+    let xml = xml.map_err(|_| {
+        format!(
+            "{} exited with code {} before writing any results. Check the output for errors.",
+            runner, exit_code
+        )
+    })?;
+
+    let mut summary = parse_junit(&xml);
+    summary.runner = runner.to_string();
+    summary.exit_code = exit_code;
+    Ok(summary)
+}
+
+/// Runs Laravel Pint on the project: with `fix` it rewrites files, otherwise it only
+/// reports which files don't match the code style (`--test`). Anything Pint prints besides
+/// the report is streamed to `pint-output-<project_id>`.
+#[command(async)]
+pub fn run_pint(
+    project_id: String,
+    fix: bool,
+    app_handle: AppHandle,
+) -> Result<PintResult, String> {
+    let location = project_location(&project_id)?;
+    let pint = Path::new("vendor").join("bin").join("pint");
+    if !location.join(&pint).exists() {
+        return Err(
+            "Pint is not installed. Run `composer require laravel/pint --dev` first.".to_string(),
+        );
+    }
+
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(&location).arg(&pint).arg("--format=json");
+    if !fix {
+        cmd.arg("--test");
+    }
+    let (exit_code, stdout) = run_captured(
+        cmd,
+        &project_id,
+        "pint".to_string(),
+        &format!("pint-output-{}", project_id),
+        &app_handle,
+        |e| {
+            format!(
+                "Failed to run Pint: {}. Make sure PHP is installed and in your PATH.",
+                e
+            )
+        },
+    )?;
+
+    // Exit code 1 only means `--test` found files to fix; anything else without a report failed
+    let files = parse_pint_report(&stdout).ok_or_else(|| {
+        let output = stdout.trim();
+        if output.is_empty() {
+            format!(
+                "Pint exited with code {} without a report. Check the output for errors.",
+                exit_code
+            )
+        } else {
+            format!("Pint failed: {}", output)
+        }
+    })?;
+    Ok(PintResult { files })
+}
+
+/// Files listed in the report Pint prints with `--format=json`, or None if there's no
+/// readable report in the output.
+fn parse_pint_report(output: &str) -> Option<Vec<PintFile>> {
+    let report: PintReport = serde_json::from_str(json_report(output)?).ok()?;
+    Some(report.files)
+}
+
+/// The JSON report Pint prints with `--format=json`.
+#[derive(Deserialize)]
+struct PintReport {
+    #[serde(default)]
+    files: Vec<PintFile>,
+}
+
+/// PHPStan's findings, grouped by file.
+#[derive(Debug, Serialize)]
+pub struct PhpstanResult {
+    pub total_errors: u32,
+    pub files: Vec<PhpstanFile>,
+    pub errors: Vec<String>, // Errors not tied to a file, e.g. a bad config
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhpstanFile {
+    pub path: String, // Absolute, so it can be passed to open_in_editor
+    pub errors: Vec<PhpstanError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhpstanError {
+    pub message: String,
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub identifier: Option<String>, // e.g. "argument.type", for ignoring it in phpstan.neon
+    #[serde(default)]
+    pub tip: Option<String>,
+}
+
+/// The JSON report PHPStan prints with `--error-format=json`.
+#[derive(Deserialize)]
+struct PhpstanReport {
+    // Keyed by path, but an empty `[]` when no file has errors
+    #[serde(default)]
+    files: serde_json::Value,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PhpstanReportFile {
+    messages: Vec<PhpstanError>,
+}
+
+/// Runs PHPStan (or Larastan, which installs the same binary) on the project. `level`
+/// overrides the one in phpstan.neon and is 0-10 or "max". Progress is streamed to
+/// `phpstan-output-<project_id>` since analysing a large project takes a while.
+#[command(async)]
+pub fn run_phpstan(
+    project_id: String,
+    level: Option<String>,
+    app_handle: AppHandle,
+) -> Result<PhpstanResult, String> {
+    let location = project_location(&project_id)?;
+    let phpstan = Path::new("vendor").join("bin").join("phpstan");
+    if !location.join(&phpstan).exists() {
+        return Err(
+            "PHPStan is not installed. Run `composer require larastan/larastan --dev` first."
+                .to_string(),
+        );
+    }
+
+    let mut cmd = std::process::Command::new("php");
+    cmd.current_dir(&location)
+        .arg(&phpstan)
+        .arg("analyse")
+        .arg("--error-format=json")
+        .arg("--no-interaction");
+    if let Some(level) = level.filter(|l| !l.is_empty()) {
+        if level != "max" && level.parse::<u8>().is_err() {
+            return Err(format!("Invalid PHPStan level '{}'", level));
+        }
+        cmd.arg(format!("--level={}", level));
+    }
+
+    // The report goes to stdout; the progress bar and any crash output go to stderr
+    let (exit_code, stdout) = run_captured(
+        cmd,
+        &project_id,
+        "phpstan".to_string(),
+        &format!("phpstan-output-{}", project_id),
+        &app_handle,
+        |e| {
+            format!(
+                "Failed to run PHPStan: {}. Make sure PHP is installed and in your PATH.",
+                e
+            )
+        },
+    )?;
+
+    // Exit code 1 means errors were found; without a report PHPStan itself failed
+    parse_phpstan_report(&stdout).map_err(|e| {
+        format!(
+            "{} (PHPStan exited with code {}). Check the output for errors.",
+            e, exit_code
+        )
+    })
+}
+
+/// The JSON object in a tool's output, ignoring anything printed around it.
+fn json_report(output: &str) -> Option<&str> {
+    let start = output.find('{')?;
+    let end = output.rfind('}')?;
+    output.get(start..=end)
+}
+
+/// Reads the report PHPStan prints with `--error-format=json`.
+fn parse_phpstan_report(output: &str) -> Result<PhpstanResult, String> {
+    let report: PhpstanReport = json_report(output)
+        .ok_or_else(|| "PHPStan didn't print a report".to_string())
+        .and_then(|json| {
+            serde_json::from_str(json)
+                .map_err(|e| format!("Failed to read the PHPStan report: {}", e))
+        })?;
+
+    let files = match report.files {
+        serde_json::Value::Object(files) => files
+            .into_iter()
+            .map(|(path, file)| {
+                let file: PhpstanReportFile = serde_json::from_value(file)?;
+                Ok(PhpstanFile {
+                    path,
+                    errors: file.messages,
+                })
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()
+            .map_err(|e| format!("Failed to read the PHPStan report: {}", e))?,
+        _ => Vec::new(),
+    };
+    Ok(PhpstanResult {
+        total_errors: (files.iter().map(|f| f.errors.len()).sum::<usize>() + report.errors.len())
+            as u32,
+        files,
+        errors: report.errors,
+    })
+}
+
+/// Outcome of the `<testcase>` being read.
+enum CaseOutcome {
+    Passed,
+    Skipped,
+    Failed { kind: String, message: String },
+}
+
+struct TestCase {
+    name: String,
+    class: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    outcome: CaseOutcome,
+}
+
+/// Summarizes a JUnit XML report as PHPUnit and Pest write it: nested `<testsuite>`s of
+/// `<testcase>`s, each holding an optional `<failure>`, `<error>` or `<skipped>`. Counts come
+/// from the test cases rather than the suites' attributes so nesting doesn't double them.
+fn parse_junit(xml: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    let mut case: Option<TestCase> = None;
+    // Text of the <failure>/<error> being read
+    let mut message: Option<String> = None;
+
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        if let Some(message) = message.as_mut() {
+            message.push_str(&decode_entities(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(body) = rest.strip_prefix("<![CDATA[") {
+            let end = body.find("]]>").unwrap_or(body.len());
+            if let Some(message) = message.as_mut() {
+                message.push_str(&body[..end]);
+            }
+            rest = body.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+        let skip_to = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        if let Some(terminator) = skip_to {
+            rest = rest
+                .find(terminator)
+                .and_then(|end| rest.get(end + terminator.len()..))
+                .unwrap_or_default();
+            continue;
+        }
+
+        let Some(end) = tag_end(rest) else { break };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            match name.trim() {
+                "failure" | "error" => finish_message(&mut case, message.take()),
+                "testcase" => finish_case(&mut summary, case.take()),
+                _ => {}
+            }
+            continue;
+        }
+
+        let empty = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attrs) = match tag.split_once(char::is_whitespace) {
+            Some((name, attrs)) => (name, parse_attributes(attrs)),
+            None => (tag, HashMap::new()),
+        };
+        match name {
+            "testcase" => {
+                finish_case(&mut summary, case.take());
+                let attr = |key: &str| attrs.get(key).cloned();
+                summary.time += attr("time").and_then(|t| t.parse().ok()).unwrap_or(0.0);
+                case = Some(TestCase {
+                    name: attr("name").unwrap_or_default(),
+                    class: attr("class").or_else(|| attr("classname")),
+                    file: attr("file"),
+                    line: attr("line").and_then(|l| l.parse().ok()),
+                    outcome: CaseOutcome::Passed,
+                });
+                if empty {
+                    finish_case(&mut summary, case.take());
+                }
+            }
+            "failure" | "error" => {
+                if let Some(case) = case.as_mut() {
+                    case.outcome = CaseOutcome::Failed {
+                        kind: name.to_string(),
+                        message: attrs.get("message").cloned().unwrap_or_default(),
+                    };
+                    if !empty {
+                        message = Some(String::new());
+                    }
+                }
+            }
+            "skipped" => {
+                if let Some(case) = case.as_mut() {
+                    case.outcome = CaseOutcome::Skipped;
+                }
+            }
+            _ => {}
+        }
+    }
+    finish_case(&mut summary, case);
+    summary
+}
+
+/// Stores the text of a `<failure>`/`<error>` (the message and stack trace) on the case,
+/// unless the element's `message` attribute already provided one.
+fn finish_message(case: &mut Option<TestCase>, text: Option<String>) {
+    if let (Some(case), Some(text)) = (case.as_mut(), text) {
+        if let CaseOutcome::Failed { message, .. } = &mut case.outcome {
+            if message.is_empty() {
+                *message = text.trim().to_string();
+            }
+        }
+    }
+}
+
+fn finish_case(summary: &mut TestSummary, case: Option<TestCase>) {
+    let Some(case) = case else { return };
+    summary.tests += 1;
+    match case.outcome {
+        CaseOutcome::Passed => summary.passed += 1,
+        CaseOutcome::Skipped => summary.skipped += 1,
+        CaseOutcome::Failed { kind, message } => {
+            summary.failed += 1;
+            summary.failures.push(TestFailure {
+                name: case.name,
+                class: case.class,
+                file: case.file,
+                line: case.line,
+                kind,
+                message,
+            });
+        }
+    }
+}
+
+/// Index of the `>` closing the tag at the start of `s`, skipping any inside quoted values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `key="value"` pairs; single quotes are accepted too.
+fn parse_attributes(attrs: &str) -> HashMap<String, String> {
+    let mut parsed = HashMap::new();
+    let mut rest = attrs;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        parsed.insert(key, decode_entities(&value[1..end + 1]));
+        rest = &value[end + 2..];
+    }
+    parsed
+}
+
+/// Decodes the predefined XML entities and numeric character references.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else { break };
+        let entity = &rest[1..semi];
+        let replacement = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match replacement {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHPUNIT_REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="Test Suite" tests="5" assertions="6" errors="1" failures="1" skipped="1" time="0.250000">
+    <testsuite name="Unit" tests="2" time="0.050000">
+      <testsuite name="Tests\Unit\PriceTest" file="tests/Unit/PriceTest.php" tests="2">
+        <testcase name="test_rounds_half_up" file="/app/tests/Unit/PriceTest.php" line="12" class="Tests\Unit\PriceTest" classname="Tests.Unit.PriceTest" assertions="1" time="0.020000"/>
+        <testcase name="test_compares_&lt;amounts&gt; &amp; currencies" file="/app/tests/Unit/PriceTest.php" line="20" class="Tests\Unit\PriceTest" assertions="1" time="0.030000">
+          <failure type="PHPUnit\Framework\ExpectationFailedException"><![CDATA[Tests\Unit\PriceTest::test_compares
+Failed asserting that 'a < b' is true.
+
+/app/tests/Unit/PriceTest.php:24]]></failure>
+        </testcase>
+      </testsuite>
+    </testsuite>
+    <testsuite name="Feature" tests="3" time="0.200000">
+      <!-- <testcase name="commented out"/> -->
+      <testcase name="test_home_page" file="/app/tests/Feature/HomeTest.php" line="8" class="Tests\Feature\HomeTest" time="0.100000">
+        <error type="Error">Call to undefined method &quot;App\Home::render()&quot; &#x2014; &#39;oops&#39;</error>
+      </testcase>
+      <testcase name="test_mail" file="/app/tests/Feature/MailTest.php" line="5" class="Tests\Feature\MailTest" time="0.010000">
+        <skipped/>
+      </testcase>
+      <testcase name="test_login" class="Tests\Feature\LoginTest" time="0.090000"></testcase>
+    </testsuite>
+  </testsuite>
+</testsuites>
+"#;
+
+    const PEST_REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="Tests\Feature\ExampleTest" file="tests/Feature/ExampleTest.php" tests="2" assertions="2" errors="0" failures="1" skipped="0" time="0.120000">
+    <testcase name="it returns a successful response" file="tests/Feature/ExampleTest.php::it returns a successful response" class="Tests\Feature\ExampleTest" classname="Tests.Feature.ExampleTest" assertions="1" time="0.100000"/>
+    <testcase name="it has a title" file="tests/Feature/ExampleTest.php::it has a title" class="Tests\Feature\ExampleTest" classname="Tests.Feature.ExampleTest" assertions="1" time="0.020000">
+      <failure type="PHPUnit\Framework\ExpectationFailedException" message="Failed asserting that 'Laravel' is identical to 'Workshop'.">Failed asserting that 'Laravel' is identical to 'Workshop'.
+at tests/Feature/ExampleTest.php:9</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+
+    #[test]
+    fn phpunit_report_is_summarized_from_its_test_cases() {
+        let summary = parse_junit(PHPUNIT_REPORT);
+        assert_eq!(summary.tests, 5);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.skipped, 1);
+        assert!((summary.time - 0.25).abs() < 1e-9);
+
+        let [failure, error] = &summary.failures[..] else {
+            panic!("expected two failures, got {:?}", summary.failures);
+        };
+        assert_eq!(failure.kind, "failure");
+        assert_eq!(failure.name, "test_compares_<amounts> & currencies");
+        assert_eq!(failure.class.as_deref(), Some("Tests\\Unit\\PriceTest"));
+        assert_eq!(failure.line, Some(20));
+        assert_eq!(
+            failure.message,
+            "Tests\\Unit\\PriceTest::test_compares\nFailed asserting that 'a < b' is true.\n\n/app/tests/Unit/PriceTest.php:24"
+        );
+
+        assert_eq!(error.kind, "error");
+        assert_eq!(
+            error.file.as_deref(),
+            Some("/app/tests/Feature/HomeTest.php")
+        );
+        assert_eq!(
+            error.message,
+            "Call to undefined method \"App\\Home::render()\" \u{2014} 'oops'"
+        );
+    }
+
+    #[test]
+    fn pest_report_prefers_the_message_attribute() {
+        let summary = parse_junit(PEST_REPORT);
+        assert_eq!(summary.tests, 2);
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.failures[0].name, "it has a title");
+        assert_eq!(
+            summary.failures[0].file.as_deref(),
+            Some("tests/Feature/ExampleTest.php::it has a title")
+        );
+        assert_eq!(
+            summary.failures[0].message,
+            "Failed asserting that 'Laravel' is identical to 'Workshop'."
+        );
+    }
+
+    #[test]
+    fn attributes_accept_both_quotes_and_decode_entities() {
+        let attrs = parse_attributes(r#"name="a &amp; b" file='it "works"'  line = "3""#);
+        assert_eq!(attrs["name"], "a & b");
+        assert_eq!(attrs["file"], "it \"works\"");
+        assert_eq!(attrs["line"], "3");
+        assert!(parse_attributes("broken=unquoted").is_empty());
+    }
+
+    #[test]
+    fn entities_are_decoded() {
+        assert_eq!(
+            decode_entities("&lt;tag&gt; &amp;&amp; &quot;q&quot; &apos;a&apos;"),
+            "<tag> && \"q\" 'a'"
+        );
+        assert_eq!(decode_entities("&#65;&#x42;&#x1F600;"), "AB\u{1F600}");
+        // Unknown or unterminated entities are kept as written
+        assert_eq!(decode_entities("&nbsp; &#xZZ; AT&T"), "&nbsp; &#xZZ; AT&T");
+    }
+
+    #[test]
+    fn phpstan_report_without_errors() {
+        let result = parse_phpstan_report(
+            r#"{"totals":{"errors":0,"file_errors":0},"files":[],"errors":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(result.total_errors, 0);
+        assert!(result.files.is_empty());
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn phpstan_report_with_file_messages() {
+        let output = r#"Note: Using configuration file /app/phpstan.neon.
+{"totals":{"errors":0,"file_errors":3},"files":{"/app/app/Models/User.php":{"errors":2,"messages":[{"message":"Parameter #1 $id of method App\\Models\\User::find() expects int, string given.","line":12,"ignorable":true,"identifier":"argument.type"},{"message":"Method App\\Models\\User::posts() has no return type specified.","line":30,"ignorable":true,"tip":"Add a return type to the method.","identifier":"missingType.return"}]},"/app/routes/web.php":{"errors":1,"messages":[{"message":"Undefined variable: $user","line":null,"ignorable":false}]}},"errors":[]}
+"#;
+        let result = parse_phpstan_report(output).unwrap();
+        assert_eq!(result.total_errors, 3);
+        let user = result
+            .files
+            .iter()
+            .find(|file| file.path == "/app/app/Models/User.php")
+            .unwrap();
+        assert_eq!(user.errors.len(), 2);
+        assert_eq!(user.errors[0].line, Some(12));
+        assert_eq!(user.errors[0].identifier.as_deref(), Some("argument.type"));
+        assert_eq!(
+            user.errors[1].tip.as_deref(),
+            Some("Add a return type to the method.")
+        );
+        let routes = result
+            .files
+            .iter()
+            .find(|file| file.path == "/app/routes/web.php")
+            .unwrap();
+        assert_eq!(routes.errors[0].line, None);
+        assert_eq!(routes.errors[0].identifier, None);
+    }
+
+    #[test]
+    fn phpstan_report_with_general_errors() {
+        let result = parse_phpstan_report(
+            r#"{"totals":{"errors":1,"file_errors":0},"files":[],"errors":["Ignored error pattern #^Foo$# was not matched in reported errors."]}"#,
+        )
+        .unwrap();
+        assert_eq!(result.total_errors, 1);
+        assert!(result.files.is_empty());
+        assert_eq!(
+            result.errors,
+            ["Ignored error pattern #^Foo$# was not matched in reported errors."]
+        );
+
+        assert!(parse_phpstan_report("PHP Fatal error: Allowed memory size exhausted").is_err());
+    }
+
+    #[test]
+    fn pint_report_lists_the_files_and_their_fixers() {
+        // Captured from `pint --format=json --test`, with a deprecation notice PHP printed first
+        let output = r#"PHP Deprecated:  Creation of dynamic property in vendor/x.php on line 3
+{"about":"PHP CS Fixer 3.64.0 7th Gear by Fabien Potencier, Dariusz Ruminski and contributors.","files":[{"name":"app\/Models\/User.php","appliedFixers":["no_unused_imports","ordered_imports"]},{"name":"routes\/web.php","appliedFixers":["single_quote"]}],"time":{"total":0.412},"memory":18}
+"#;
+        let files = parse_pint_report(output).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "app/Models/User.php");
+        assert_eq!(files[0].fixers, ["no_unused_imports", "ordered_imports"]);
+        assert_eq!(files[1].path, "routes/web.php");
+    }
+
+    #[test]
+    fn pint_report_without_changes() {
+        let output = r#"{"about":"PHP CS Fixer","files":[],"time":{"total":0.2},"memory":16}"#;
+        assert!(parse_pint_report(output).unwrap().is_empty());
+
+        // `appliedFixers` and `files` are optional
+        let files = parse_pint_report(r#"{"files":[{"name":"a.php"}]}"#).unwrap();
+        assert!(files[0].fixers.is_empty());
+        assert!(parse_pint_report("{}").unwrap().is_empty());
+
+        assert!(parse_pint_report("Could not open input file: vendor/bin/pint").is_none());
+    }
+}
@@ -1,16 +1,25 @@
-use crate::state::{AppState, TerminalSession};
+use crate::state::{AppState, Scrollback, TerminalSession};
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use tauri::{Emitter, State};
 
+const DEFAULT_SCROLLBACK_BYTES: usize = 64 * 1024;
+const MAX_SCROLLBACK_BYTES: usize = 4 * 1024 * 1024;
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_pty(
     id: String,
     cwd: Option<String>,
     rows: u16,
     cols: u16,
+    scrollback_bytes: Option<usize>,
+    shell: Option<String>,
+    env: Option<HashMap<String, String>>,
     state: State<'_, Arc<AppState>>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
@@ -26,15 +35,15 @@ pub fn spawn_pty(
         })
         .map_err(|e| e.to_string())?;
 
-    // Determine shell (default to zsh on Mac, fallback to sh)
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-
-    // Use login shell to load user's profile (.zshrc, .bash_profile, etc.)
+    let (default, args) = default_shell();
+    let shell = match shell.filter(|s| !s.trim().is_empty()) {
+        Some(shell) => resolve_shell(&shell)?,
+        None => default,
+    };
     let mut cmd = CommandBuilder::new(&shell);
-    cmd.arg("-l"); // Login shell flag - loads user profile
-    cmd.arg("-i"); // Interactive mode
+    cmd.args(args);
 
-    if let Some(dir) = cwd {
+    if let Some(dir) = &cwd {
         cmd.cwd(dir);
     }
 
@@ -57,13 +66,24 @@ pub fn spawn_pty(
         cmd.env("USER", user);
     }
 
-    let mut _child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
+    // Caller-supplied variables (e.g. a PATH pinned to a PHP version) win over the above
+    for (key, value) in env.unwrap_or_default() {
+        cmd.env(key, value);
+    }
+
+    let child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
 
     // Clone the reader for the thread
     let mut reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
     let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
 
-    // Store the session (writer + master) in state
+    let scrollback = Arc::new(Mutex::new(Scrollback::new(
+        scrollback_bytes
+            .unwrap_or(DEFAULT_SCROLLBACK_BYTES)
+            .min(MAX_SCROLLBACK_BYTES),
+    )));
+
+    // Store the session (writer + master + child) in state
     {
         let mut sessions = state.terminal_sessions.lock().unwrap();
         sessions.insert(
@@ -71,6 +91,9 @@ pub fn spawn_pty(
             TerminalSession {
                 writer,
                 master: pair.master,
+                child,
+                cwd,
+                scrollback: scrollback.clone(),
             },
         );
     }
@@ -82,9 +105,10 @@ pub fn spawn_pty(
         loop {
             match reader.read(&mut buffer) {
                 Ok(n) if n > 0 => {
+                    scrollback.lock().unwrap().push(&buffer[..n]);
                     let output = String::from_utf8_lossy(&buffer[..n]).to_string();
                     if let Err(e) = app_handle.emit(&format!("pty-output-{}", pty_id), output) {
-                        eprintln!("Failed to emit to frontend: {}", e);
+                        tracing::error!(pty_id = %pty_id, error = %e, "Failed to emit to frontend");
                         break;
                     }
                 }
@@ -98,6 +122,48 @@ pub fn spawn_pty(
     Ok(())
 }
 
+/// Checks that a shell override exists, either as a path or as a program on PATH.
+fn resolve_shell(shell: &str) -> Result<String, String> {
+    let path = Path::new(shell);
+    if path.components().count() > 1 {
+        return if path.is_file() {
+            Ok(shell.to_string())
+        } else {
+            Err(format!("Shell not found: {}", shell))
+        };
+    }
+
+    let extensions: &[&str] = if cfg!(windows) { &["", ".exe"] } else { &[""] };
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", shell, ext)))
+        })
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| candidate.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Shell not found on PATH: {}", shell))
+}
+
+/// The user's shell and the arguments to start it with.
+#[cfg(not(windows))]
+fn default_shell() -> (String, &'static [&'static str]) {
+    // Default to zsh on Mac. Use a login shell to load the user's profile (.zshrc,
+    // .bash_profile, etc.) in interactive mode.
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+    (shell, &["-l", "-i"])
+}
+
+/// The user's shell and the arguments to start it with.
+#[cfg(windows)]
+fn default_shell() -> (String, &'static [&'static str]) {
+    // COMSPEC points at cmd.exe unless the user swapped it for PowerShell; neither takes -l/-i
+    let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+    (shell, &[])
+}
+
 #[tauri::command]
 pub fn write_pty(id: String, data: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
     let mut sessions = state.terminal_sessions.lock().unwrap();
@@ -128,3 +194,132 @@ pub fn resize_pty(
     }
     Ok(())
 }
+
+#[tauri::command]
+pub fn kill_pty(id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    let session = state
+        .terminal_sessions
+        .lock()
+        .unwrap()
+        .remove(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?;
+
+    let TerminalSession {
+        writer,
+        master,
+        mut child,
+        ..
+    } = session;
+
+    // Sends SIGHUP on unix, like closing a terminal window would
+    let _ = child.kill();
+
+    // Dropping the writer and master closes the PTY, so the reader thread sees EOF and exits
+    drop(writer);
+    drop(master);
+
+    // Reap the shell in the background so it doesn't linger as a zombie
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
+/// Sends a signal to the terminal's foreground process group, like the tty driver does for
+/// Ctrl-C. Works even when the running program has put the terminal in raw mode.
+#[tauri::command]
+pub fn signal_pty(
+    id: String,
+    signal: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    let mut sessions = state.terminal_sessions.lock().unwrap();
+    let session = sessions
+        .get_mut(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?;
+
+    #[cfg(unix)]
+    {
+        let signum = match signal.to_uppercase().as_str() {
+            "SIGINT" | "INT" => libc::SIGINT,
+            "SIGTERM" | "TERM" => libc::SIGTERM,
+            "SIGHUP" | "HUP" => libc::SIGHUP,
+            "SIGQUIT" | "QUIT" => libc::SIGQUIT,
+            "SIGKILL" | "KILL" => libc::SIGKILL,
+            _ => return Err(format!("Unsupported signal: {}", signal)),
+        };
+        let pgid = session
+            .master
+            .process_group_leader()
+            .ok_or("Could not determine the terminal's foreground process")?;
+
+        if unsafe { libc::killpg(pgid, signum) } != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        match signal.to_uppercase().as_str() {
+            // ConPTY turns Ctrl-C into a console control event for the foreground process
+            "SIGINT" | "INT" => session.writer.write_all(b"\x03").map_err(|e| e.to_string()),
+            "SIGTERM" | "TERM" => session.child.kill().map_err(|e| e.to_string()),
+            _ => Err(format!("Unsupported signal: {}", signal)),
+        }
+    }
+}
+
+/// Recent output of a session, for repainting a terminal after the frontend reloads.
+#[tauri::command]
+pub fn get_pty_scrollback(id: String, state: State<'_, Arc<AppState>>) -> Result<String, String> {
+    let sessions = state.terminal_sessions.lock().unwrap();
+    let session = sessions
+        .get(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?;
+    let contents = session.scrollback.lock().unwrap().contents();
+    Ok(contents)
+}
+
+/// Returns the terminal's current directory: the foreground process's live cwd where the
+/// platform exposes it, otherwise the directory the session was spawned in.
+#[tauri::command]
+pub fn get_pty_cwd(id: String, state: State<'_, Arc<AppState>>) -> Result<Option<String>, String> {
+    let sessions = state.terminal_sessions.lock().unwrap();
+    let session = sessions
+        .get(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?;
+
+    #[cfg(unix)]
+    if let Some(cwd) = session.master.process_group_leader().and_then(process_cwd) {
+        return Ok(Some(cwd));
+    }
+
+    Ok(session.cwd.clone())
+}
+
+#[cfg(target_os = "linux")]
+fn process_cwd(pid: i32) -> Option<String> {
+    std::fs::read_link(format!("/proc/{}/cwd", pid))
+        .ok()
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn process_cwd(pid: i32) -> Option<String> {
+    // No /proc on macOS; lsof prints the cwd as an "n<path>" field
+    let output = std::process::Command::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('n'))
+        .map(|path| path.to_string())
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn process_cwd(_pid: i32) -> Option<String> {
+    None
+}
@@ -0,0 +1,136 @@
+use crate::utils::get_db_path;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use keyring::Entry;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Marks a password that was encrypted with the machine key. Anything without it is a
+/// plaintext value saved by an older version.
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+const KEYCHAIN_SERVICE: &str = "workshop";
+
+/// Keychain entry holding a project's database password, `workshop:<project_id>:db_password`.
+fn keychain_entry(project_id: &str) -> keyring::Result<Entry> {
+    Entry::new(
+        KEYCHAIN_SERVICE,
+        &format!("workshop:{}:db_password", project_id),
+    )
+}
+
+/// Looks up a project's database password in the OS keychain.
+pub fn keychain_password(project_id: &str) -> Option<String> {
+    keychain_entry(project_id).ok()?.get_password().ok()
+}
+
+pub fn delete_keychain_password(project_id: &str) {
+    if let Ok(entry) = keychain_entry(project_id) {
+        let _ = entry.delete_password();
+    }
+}
+
+/// Moves a password out of `db_config`. Returns the value to persist there: `None` when the
+/// OS keychain took it, otherwise the encrypted password as a fallback.
+pub fn protect_password(project_id: &str, password: &str) -> Result<Option<String>, String> {
+    match keychain_entry(project_id).and_then(|entry| entry.set_password(password)) {
+        Ok(()) => Ok(None),
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                project_id,
+                "OS keychain unavailable; storing the database password encrypted in the projects database instead"
+            );
+            Ok(Some(encrypt_password(password)?))
+        }
+    }
+}
+
+/// The machine-local key lives next to the projects database, readable only by the user.
+fn key_path() -> Result<PathBuf, String> {
+    let db_path = get_db_path()?;
+    let dir = db_path
+        .parent()
+        .ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    Ok(dir.join("credentials.key"))
+}
+
+fn load_or_create_key() -> Result<Key<Aes256Gcm>, String> {
+    let path = key_path()?;
+
+    if path.exists() {
+        let bytes =
+            fs::read(&path).map_err(|e| format!("Failed to read credentials key: {}", e))?;
+        if bytes.len() != 32 {
+            return Err("Credentials key file is corrupted".to_string());
+        }
+        return Ok(*Key::<Aes256Gcm>::from_slice(&bytes));
+    }
+
+    // Created with owner-only permissions from the start, so the key is never readable by
+    // others, not even between creating and restricting the file
+    let key = Aes256Gcm::generate_key(OsRng);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = match options.open(&path) {
+        Ok(file) => file,
+        // Another thread created it first; use that key
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return load_or_create_key(),
+        Err(e) => return Err(format!("Failed to create credentials key: {}", e)),
+    };
+    if let Err(e) = file
+        .write_all(key.as_slice())
+        .and_then(|()| file.sync_all())
+    {
+        drop(file);
+        let _ = fs::remove_file(&path);
+        return Err(format!("Failed to write credentials key: {}", e));
+    }
+
+    Ok(key)
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts a password for storage in `db_config`.
+pub fn encrypt_password(plain: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(&load_or_create_key()?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain.as_bytes())
+        .map_err(|e| format!("Failed to encrypt password: {}", e))?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend(ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+}
+
+/// Decrypts a stored password. Legacy plaintext values are returned unchanged.
+pub fn decrypt_password(stored: &str) -> Result<String, String> {
+    let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+
+    let payload = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("Stored password is corrupted: {}", e))?;
+    if payload.len() < NONCE_LEN {
+        return Err("Stored password is corrupted".to_string());
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(&load_or_create_key()?);
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt stored password; please re-enter it".to_string())?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
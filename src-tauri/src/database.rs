@@ -1,6 +1,11 @@
-use crate::models::project::{Project, ProjectStatus};
-use rusqlite::{params, Connection, Result};
-use std::path::PathBuf;
+use crate::models::project::{
+    GetProjectsParams, Project, ProjectPage, ProjectSortField, ProjectStatus, SortOrder,
+};
+use crate::utils::read_git_branch;
+use chrono::Utc;
+use rusqlite::types::{Type, Value};
+use rusqlite::{params, params_from_iter, Connection, Result, Row};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,13 +14,12 @@ pub enum DatabaseError {
     RusqliteError(#[from] rusqlite::Error),
 }
 
-pub struct Database {
-    conn: Connection,
-}
+type Migration = fn(&Connection) -> Result<()>;
 
-impl Database {
-    pub fn new(path: PathBuf) -> Result<Self, DatabaseError> {
-        let conn = Connection::open(path)?;
+/// Schema migrations in order. A database at `PRAGMA user_version` N has had the first N
+/// applied, so new migrations must only ever be appended.
+const MIGRATIONS: &[Migration] = &[
+    |conn| {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS projects (
                 id TEXT PRIMARY KEY,
@@ -24,26 +28,105 @@ impl Database {
                 location TEXT,
                 status TEXT,
                 created_at TEXT,
-                updated_at TEXT,
-                db_config TEXT
+                updated_at TEXT
             )",
             [],
         )?;
-
-        // Migration for existing databases
-        let mut needs_migration = false;
-        {
-            let mut stmt = conn.prepare("PRAGMA table_info(projects)")?;
-            let rows = stmt.query_map([], |row| Ok(row.get::<_, String>(1)?))?;
-            let columns: Vec<String> = rows.filter_map(|r| r.ok()).collect();
-            if !columns.contains(&"db_config".to_string()) {
-                needs_migration = true;
+        Ok(())
+    },
+    |conn| {
+        // Databases created before versioning may already have the column
+        if !column_exists(conn, "projects", "db_config")? {
+            conn.execute("ALTER TABLE projects ADD COLUMN db_config TEXT", [])?;
+        }
+        Ok(())
+    },
+    |conn| {
+        conn.execute("ALTER TABLE projects ADD COLUMN deleted_at TEXT", [])?;
+        Ok(())
+    },
+    |conn| {
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN is_favorite INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        Ok(())
+    },
+    |conn| {
+        // db_config went from a single connection to a map of named ones
+        let mut stmt =
+            conn.prepare("SELECT id, db_config FROM projects WHERE db_config IS NOT NULL")?;
+        let configs = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        for (id, config) in configs {
+            let Ok(config) = serde_json::from_str::<serde_json::Value>(&config) else {
+                continue;
+            };
+            if config.get("connection").is_some_and(|c| c.is_string()) {
+                conn.execute(
+                    "UPDATE projects SET db_config = ?1 WHERE id = ?2",
+                    params![serde_json::json!({ "default": config }).to_string(), id],
+                )?;
             }
         }
+        Ok(())
+    },
+];
 
-        if needs_migration {
-            conn.execute("ALTER TABLE projects ADD COLUMN db_config TEXT", [])?;
-        }
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(columns.iter().any(|c| c == column))
+}
+
+/// Applies any migrations newer than the database's `user_version`, each in its own
+/// transaction.
+fn run_migrations(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn project_from_row(row: &Row) -> Result<Project> {
+    let status: String = row.get(4)?;
+    let location: String = row.get(3)?;
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        location_exists: Path::new(&location).is_dir(),
+        git_branch: read_git_branch(Path::new(&location)),
+        location,
+        status: status
+            .parse::<ProjectStatus>()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, Type::Text, e.into()))?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        db_config: row.get(7).unwrap_or(None),
+        deleted_at: row.get(8)?,
+        is_favorite: row.get(9)?,
+    })
+}
+
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn new(path: PathBuf) -> Result<Self, DatabaseError> {
+        let mut conn = Connection::open(path)?;
+        run_migrations(&mut conn)?;
         Ok(Database { conn })
     }
 
@@ -56,7 +139,7 @@ impl Database {
                 project.name,
                 project.description,
                 project.location,
-                format!("{:?}", project.status),
+                project.status.to_string(),
                 project.created_at,
                 project.updated_at,
                 project.db_config
@@ -66,26 +149,11 @@ impl Database {
     }
 
     pub fn get_projects(&self) -> Result<Vec<Project>, DatabaseError> {
-        let mut stmt = self.conn.prepare("SELECT * FROM projects")?;
-        let project_iter = stmt.query_map([], |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                location: row.get(3)?,
-                status: match row.get::<_, String>(4)?.as_str() {
-                    "InProgress" => ProjectStatus::InProgress,
-                    "Completed" => ProjectStatus::Completed,
-                    "InitialStage" => ProjectStatus::InitialStage,
-                    "OnHold" => ProjectStatus::OnHold,
-                    "Abandoned" => ProjectStatus::Abandoned,
-                    _ => ProjectStatus::InProgress,
-                },
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-                db_config: row.get(7).unwrap_or(None),
-            })
-        })?;
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM projects WHERE deleted_at IS NULL
+             ORDER BY is_favorite DESC, updated_at DESC",
+        )?;
+        let project_iter = stmt.query_map([], project_from_row)?;
 
         let mut projects = Vec::new();
         for project in project_iter {
@@ -94,6 +162,73 @@ impl Database {
         Ok(projects)
     }
 
+    /// Lists projects outside the recycle bin matching `params`, along with the total number
+    /// of matches for paging.
+    pub fn query_projects(&self, params: &GetProjectsParams) -> Result<ProjectPage, DatabaseError> {
+        let mut conditions = vec!["deleted_at IS NULL".to_string()];
+        let mut values: Vec<Value> = Vec::new();
+
+        if let Some(search) = params
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let escaped = search
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            values.push(Value::Text(format!("%{}%", escaped)));
+            conditions.push(format!(
+                "(name LIKE ?{0} ESCAPE '\\' OR description LIKE ?{0} ESCAPE '\\')",
+                values.len()
+            ));
+        }
+        if let Some(status) = &params.status {
+            values.push(Value::Text(status.to_string()));
+            conditions.push(format!("status = ?{}", values.len()));
+        }
+        let where_clause = conditions.join(" AND ");
+
+        let total: u64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM projects WHERE {}", where_clause),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        // Only whitelisted column names are interpolated; everything else is bound
+        let sort_column = match params.sort_by {
+            ProjectSortField::Name => "name COLLATE NOCASE",
+            ProjectSortField::CreatedAt => "created_at",
+            ProjectSortField::UpdatedAt => "updated_at",
+        };
+        let order = match params.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let mut sql = format!(
+            "SELECT * FROM projects WHERE {} ORDER BY is_favorite DESC, {} {}",
+            where_clause, sort_column, order
+        );
+        if params.limit.is_some() || params.offset.is_some() {
+            // SQLite needs a LIMIT before OFFSET; -1 means no limit
+            values.push(Value::Integer(params.limit.map_or(-1, i64::from)));
+            values.push(Value::Integer(i64::from(params.offset.unwrap_or(0))));
+            sql.push_str(&format!(
+                " LIMIT ?{} OFFSET ?{}",
+                values.len() - 1,
+                values.len()
+            ));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let projects = stmt
+            .query_map(params_from_iter(values.iter()), project_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ProjectPage { projects, total })
+    }
+
     pub fn update_project(&self, id: &str, updates: &Project) -> Result<(), DatabaseError> {
         self.conn.execute(
             "UPDATE projects
@@ -103,7 +238,7 @@ impl Database {
                 updates.name,
                 updates.description,
                 updates.location,
-                format!("{:?}", updates.status),
+                updates.status.to_string(),
                 updates.updated_at,
                 updates.db_config,
                 id
@@ -112,10 +247,57 @@ impl Database {
         Ok(())
     }
 
+    /// Flips a project's favorite flag, returning the new value, or `None` if there's no
+    /// such project.
+    pub fn toggle_favorite(&self, id: &str) -> Result<Option<bool>, DatabaseError> {
+        let affected = self.conn.execute(
+            "UPDATE projects SET is_favorite = NOT is_favorite WHERE id = ?1",
+            params![id],
+        )?;
+        if affected == 0 {
+            return Ok(None);
+        }
+        let is_favorite = self.conn.query_row(
+            "SELECT is_favorite FROM projects WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(Some(is_favorite))
+    }
+
+    /// Moves a project to the recycle bin. Nothing on disk is touched.
     pub fn delete_project(&self, id: &str) -> Result<bool, DatabaseError> {
-        let affected = self
-            .conn
-            .execute("DELETE FROM projects WHERE id = ?1", params![id])?;
+        let affected = self.conn.execute(
+            "UPDATE projects SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    pub fn restore_project(&self, id: &str) -> Result<bool, DatabaseError> {
+        let affected = self.conn.execute(
+            "UPDATE projects SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    pub fn get_deleted_projects(&self) -> Result<Vec<Project>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM projects WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )?;
+        let projects = stmt
+            .query_map([], project_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(projects)
+    }
+
+    /// Permanently removes a project. Only projects already in the recycle bin can be purged.
+    pub fn purge_project(&self, id: &str) -> Result<bool, DatabaseError> {
+        let affected = self.conn.execute(
+            "DELETE FROM projects WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
         Ok(affected > 0)
     }
 
@@ -123,26 +305,125 @@ impl Database {
         let mut stmt = self.conn.prepare("SELECT * FROM projects WHERE id = ?1")?;
         let mut rows = stmt.query(params![id])?;
         if let Some(row) = rows.next()? {
-            let project = Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                location: row.get(3)?,
-                status: match row.get::<_, String>(4)?.as_str() {
-                    "InProgress" => ProjectStatus::InProgress,
-                    "Completed" => ProjectStatus::Completed,
-                    "InitialStage" => ProjectStatus::InitialStage,
-                    "OnHold" => ProjectStatus::OnHold,
-                    "Abandoned" => ProjectStatus::Abandoned,
-                    _ => ProjectStatus::InProgress,
-                },
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-                db_config: row.get(7).unwrap_or(None),
-            };
-            Ok(Some(project))
+            Ok(Some(project_from_row(row)?))
         } else {
             Ok(None)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_CONFIG: &str = r#"{"connection":"mysql","host":"127.0.0.1","database":"shop"}"#;
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn db_config(conn: &Connection, id: &str) -> Option<String> {
+        conn.query_row(
+            "SELECT db_config FROM projects WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    /// The `projects` table as versions before `user_version` tracking created it.
+    fn legacy_database(with_db_config: bool) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        let db_config = if with_db_config {
+            ", db_config TEXT"
+        } else {
+            ""
+        };
+        conn.execute_batch(&format!(
+            "CREATE TABLE projects (
+                id TEXT PRIMARY KEY, name TEXT NOT NULL, description TEXT, location TEXT,
+                status TEXT, created_at TEXT, updated_at TEXT{}
+            );
+            INSERT INTO projects (id, name, description, location, status, created_at, updated_at)
+            VALUES ('p1', 'Shop', '', '/tmp/shop', 'InProgress', '2024-01-01', '2024-01-01');",
+            db_config
+        ))
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn legacy_database_without_db_config_is_migrated() {
+        let mut conn = legacy_database(false);
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert!(column_exists(&conn, "projects", "db_config").unwrap());
+        assert!(column_exists(&conn, "projects", "is_favorite").unwrap());
+        assert_eq!(db_config(&conn, "p1"), None);
+    }
+
+    #[test]
+    fn legacy_database_with_db_config_is_migrated() {
+        let mut conn = legacy_database(true);
+        conn.execute(
+            "UPDATE projects SET db_config = ?1 WHERE id = 'p1'",
+            params![LEGACY_CONFIG],
+        )
+        .unwrap();
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert!(column_exists(&conn, "projects", "deleted_at").unwrap());
+
+        let config: serde_json::Value =
+            serde_json::from_str(&db_config(&conn, "p1").unwrap()).unwrap();
+        assert_eq!(config["default"]["database"], "shop");
+    }
+
+    #[test]
+    fn rerunning_migrations_changes_nothing() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO projects (id, name, status, db_config) VALUES ('p1', 'Shop', 'InProgress', ?1)",
+            params![LEGACY_CONFIG],
+        )
+        .unwrap();
+
+        // Already at the latest version, so the db_config migration doesn't run again
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(db_config(&conn, "p1").as_deref(), Some(LEGACY_CONFIG));
+    }
+
+    #[test]
+    fn db_config_migration_wraps_single_connections_only() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        let mapped = format!(
+            r#"{{"default":{},"reporting":{}}}"#,
+            LEGACY_CONFIG, LEGACY_CONFIG
+        );
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, status) VALUES ('none', 'None', 'InProgress')",
+        )
+        .unwrap();
+        for (id, config) in [("single", LEGACY_CONFIG), ("mapped", mapped.as_str())] {
+            conn.execute(
+                "INSERT INTO projects (id, name, status, db_config) VALUES (?1, ?1, 'InProgress', ?2)",
+                params![id, config],
+            )
+            .unwrap();
+        }
+        // Back to just before the db_config migration
+        conn.pragma_update(None, "user_version", 4).unwrap();
+        run_migrations(&mut conn).unwrap();
+
+        let single: serde_json::Value =
+            serde_json::from_str(&db_config(&conn, "single").unwrap()).unwrap();
+        let legacy: serde_json::Value = serde_json::from_str(LEGACY_CONFIG).unwrap();
+        assert_eq!(single, serde_json::json!({ "default": legacy }));
+        assert_eq!(db_config(&conn, "mapped").as_deref(), Some(mapped.as_str()));
+        assert_eq!(db_config(&conn, "none"), None);
+    }
+}
@@ -1,188 +1,1121 @@
-use crate::models::db_types::{ColumnDetail, DbCredentials, TableData};
+use crate::error::{CommandError, CommandResult};
+use crate::models::db_types::{
+    ColumnDefinition, ColumnDetail, ColumnStats, DbCredentials, FailedStatement, FilterOperator,
+    ForeignKey, IndexInfo, RedisKeys, RedisValue, RowCount, RowFilter, SqlScriptResult, TableData,
+};
+use crate::redis_backend::RedisBackend;
 use mysql::prelude::*;
-use mysql::{consts::ColumnType, params, OptsBuilder, Pool, Value as MySqlValue};
+use mysql::{consts::ColumnType, OptsBuilder, Pool, QueryResult, TxOpts, Value as MySqlValue};
 use rusqlite::{types::Value as SqliteValue, Connection};
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A single result row keyed by column name; `None` represents SQL NULL.
+type RowData = HashMap<String, Option<String>>;
+
+/// Returned by `update_row` when the row no longer holds the values the caller loaded.
+const ROW_MODIFIED_ERROR: &str =
+    "Row was modified by someone else since it was loaded. Reload the data and try again.";
+
+/// MySQL schemas that are hidden from `get_databases` unless explicitly requested.
+const MYSQL_SYSTEM_SCHEMAS: [&str; 4] =
+    ["information_schema", "mysql", "performance_schema", "sys"];
+
+/// Row offset for a 1-based page number. Computed in `u64` so large page numbers on
+/// big tables can't overflow, and page 0 is rejected rather than wrapping around.
+fn page_offset(page: u32, per_page: u32) -> Result<u64, String> {
+    if page == 0 {
+        return Err("Invalid page number 0: pages start at 1".to_string());
+    }
+    Ok((page as u64 - 1) * per_page as u64)
+}
+
+/// Rejects identifiers that can't be quoted safely: empty names, control characters
+/// (including NUL) and names longer than the 64 characters MySQL allows.
+pub fn validate_identifier(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Identifier must not be empty".to_string());
+    }
+    if name.chars().count() > 64 {
+        return Err(format!(
+            "Identifier '{}' is longer than 64 characters",
+            name
+        ));
+    }
+    if name.chars().any(|c| c.is_control()) {
+        return Err(format!("Identifier '{}' contains control characters", name));
+    }
+    Ok(())
+}
+
+/// Uppercased first keyword of a statement, ignoring leading whitespace and comments.
+fn first_keyword(statement: &str) -> String {
+    let mut rest = statement.trim_start();
+    // Skip leading comments so "-- note\nSELECT ..." is still treated as a query
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            break;
+        }
+        rest = rest.trim_start();
+    }
+
+    rest.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Whether a statement produces a result set. Anything else (INSERT, UPDATE, DDL, ...)
+/// is reported to the caller as an affected row count.
+fn returns_rows(statement: &str) -> bool {
+    matches!(
+        first_keyword(statement).as_str(),
+        "SELECT" | "SHOW" | "DESCRIBE" | "DESC" | "EXPLAIN" | "PRAGMA" | "WITH" | "VALUES"
+    )
+}
+
+/// Renders `a = ? AND b = ?` for a (possibly composite) primary key, returning the values
+/// to bind in the same order.
+fn build_pk_predicate(
+    pk: &[(String, String)],
+    quote_identifier: fn(&str) -> String,
+) -> Result<(String, Vec<String>), String> {
+    if pk.is_empty() {
+        return Err("At least one primary key column is required".to_string());
+    }
+
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+    for (column, value) in pk {
+        validate_identifier(column)?;
+        conditions.push(format!("{} = ?", quote_identifier(column)));
+        values.push(value.clone());
+    }
+    Ok((conditions.join(" AND "), values))
+}
+
+/// Rows written per `INSERT` statement in a dump.
+const DUMP_BATCH_ROWS: usize = 500;
+
+/// `INSERT INTO t (a, b) VALUES (...), (...);` from column names and rows of values that are
+/// already rendered as SQL literals.
+fn render_insert(
+    table_name: &str,
+    columns: &[String],
+    rows: &[Vec<String>],
+    quote_identifier: fn(&str) -> String,
+) -> String {
+    let columns: Vec<String> = columns.iter().map(|c| quote_identifier(c)).collect();
+    let rows: Vec<String> = rows
+        .iter()
+        .map(|values| format!("({})", values.join(", ")))
+        .collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES {};",
+        quote_identifier(table_name),
+        columns.join(", "),
+        rows.join(", ")
+    )
+}
+
+/// Writes rows as `INSERT`s of up to `DUMP_BATCH_ROWS` rows each, holding no more than one
+/// batch in memory. Returns the number of rows written.
+fn write_insert_batches<I>(
+    out: &mut dyn Write,
+    table_name: &str,
+    columns: &[String],
+    rows: I,
+    quote_identifier: fn(&str) -> String,
+) -> Result<u64, String>
+where
+    I: Iterator<Item = Result<Vec<String>, String>>,
+{
+    let mut batch = Vec::with_capacity(DUMP_BATCH_ROWS);
+    let mut written = 0;
+    for row in rows {
+        batch.push(row?);
+        if batch.len() == DUMP_BATCH_ROWS {
+            writeln!(
+                out,
+                "{}",
+                render_insert(table_name, columns, &batch, quote_identifier)
+            )
+            .map_err(|e| e.to_string())?;
+            written += batch.len() as u64;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        writeln!(
+            out,
+            "{}",
+            render_insert(table_name, columns, &batch, quote_identifier)
+        )
+        .map_err(|e| e.to_string())?;
+        written += batch.len() as u64;
+    }
+    Ok(written)
+}
+
+/// Extra `AND` conditions asserting a row still holds the values it was loaded with, for
+/// optimistic concurrency in `update_row`.
+fn build_expected_predicate(
+    expected: &HashMap<String, Option<String>>,
+    quote_identifier: fn(&str) -> String,
+) -> Result<(String, Vec<String>), String> {
+    // Sorted so the generated SQL is stable across calls
+    let mut columns: Vec<_> = expected.iter().collect();
+    columns.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut conditions = String::new();
+    let mut values = Vec::new();
+    for (column, value) in columns {
+        validate_identifier(column)?;
+        match value {
+            Some(value) => {
+                conditions.push_str(&format!(" AND {} = ?", quote_identifier(column)));
+                values.push(value.clone());
+            }
+            None => conditions.push_str(&format!(" AND {} IS NULL", quote_identifier(column))),
+        }
+    }
+    Ok((conditions, values))
+}
+
+/// Column types are interpolated into DDL, so only allow the characters real type names
+/// use (`INT UNSIGNED`, `VARCHAR(255)`, `DECIMAL(10, 2)`).
+fn validate_column_type(data_type: &str) -> Result<(), String> {
+    let valid = !data_type.trim().is_empty()
+        && data_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ' ' | '(' | ')' | ','));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid column type '{}'", data_type))
+    }
+}
+
+/// Renders a `CREATE TABLE` statement (without `IF NOT EXISTS`) using the dialect's quoting.
+/// Defaults that look numeric or are SQL keywords are emitted as-is, anything else as a string.
+fn render_create_table(
+    table_name: &str,
+    columns: &[ColumnDefinition],
+    quote_identifier: fn(&str) -> String,
+    quote_literal: fn(&str) -> String,
+) -> Result<String, String> {
+    validate_identifier(table_name)?;
+    if columns.is_empty() {
+        return Err("A table needs at least one column".to_string());
+    }
+
+    let mut definitions = Vec::new();
+    let mut primary_key = Vec::new();
+    for column in columns {
+        validate_identifier(&column.name)?;
+        validate_column_type(&column.data_type)?;
+
+        let mut definition = format!(
+            "{} {}",
+            quote_identifier(&column.name),
+            column.data_type.trim()
+        );
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(default) = &column.default {
+            let is_keyword = matches!(
+                default.to_uppercase().as_str(),
+                "NULL" | "CURRENT_TIMESTAMP" | "CURRENT_DATE" | "CURRENT_TIME"
+            );
+            if is_keyword || default.parse::<f64>().is_ok() {
+                definition.push_str(&format!(" DEFAULT {}", default));
+            } else {
+                definition.push_str(&format!(" DEFAULT {}", quote_literal(default)));
+            }
+        }
+        definitions.push(definition);
+
+        if column.primary_key {
+            primary_key.push(quote_identifier(&column.name));
+        }
+    }
+
+    if !primary_key.is_empty() {
+        definitions.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {} (\n    {}\n)",
+        quote_identifier(table_name),
+        definitions.join(",\n    ")
+    ))
+}
+
+/// `SELECT COUNT(*)` over the rows of `table_name` matching `filter`, with the values to bind.
+fn build_count_query(
+    table_name: &str,
+    filter: &RowFilter,
+    quote_identifier: fn(&str) -> String,
+) -> Result<(String, Vec<String>), String> {
+    let (where_clause, params) = build_where_clause(filter, quote_identifier)?;
+    let query = format!(
+        "SELECT COUNT(*) FROM {}{}",
+        quote_identifier(table_name),
+        where_clause
+    );
+    Ok((query, params))
+}
+
+/// Renders a row filter into a ` WHERE ...` fragment (empty when there is nothing to filter)
+/// plus the values to bind to its `?` placeholders, in order. Structured filters never
+/// interpolate values into the SQL; only the raw variant is passed through verbatim.
+fn build_where_clause(
+    filter: &RowFilter,
+    quote_identifier: fn(&str) -> String,
+) -> Result<(String, Vec<String>), String> {
+    let filters = match filter {
+        RowFilter::Raw(clause) if clause.trim().is_empty() => return Ok((String::new(), vec![])),
+        RowFilter::Raw(clause) => return Ok((format!(" WHERE {}", clause), vec![])),
+        RowFilter::Search { columns, query } => {
+            return build_search_clause(columns, query, quote_identifier)
+        }
+        RowFilter::Columns(filters) => filters,
+    };
+
+    let mut predicates = Vec::new();
+    let mut params = Vec::new();
+    for filter in filters {
+        validate_identifier(&filter.column)?;
+        let column = quote_identifier(&filter.column);
+        match filter.op {
+            FilterOperator::IsNull => predicates.push(format!("{} IS NULL", column)),
+            op => {
+                let value = filter.value.clone().ok_or_else(|| {
+                    format!(
+                        "Filter on '{}' with operator {} requires a value",
+                        filter.column,
+                        op.as_sql()
+                    )
+                })?;
+                predicates.push(format!("{} {} ?", column, op.as_sql()));
+                params.push(value);
+            }
+        }
+    }
+
+    if predicates.is_empty() {
+        Ok((String::new(), params))
+    } else {
+        Ok((format!(" WHERE {}", predicates.join(" AND ")), params))
+    }
+}
+
+/// `(a LIKE ? OR b LIKE ? ...)` with `query` bound as a `%...%` pattern. `!` is used as the
+/// LIKE escape character because MySQL and SQLite disagree on the default one.
+fn build_search_clause(
+    columns: &[String],
+    query: &str,
+    quote_identifier: fn(&str) -> String,
+) -> Result<(String, Vec<String>), String> {
+    if columns.is_empty() {
+        // Nothing to search in, so nothing can match
+        return Ok((" WHERE 1 = 0".to_string(), vec![]));
+    }
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '!' | '%' | '_') {
+            escaped.push('!');
+        }
+        escaped.push(c);
+    }
+    let pattern = format!("%{}%", escaped);
+
+    let mut predicates = Vec::with_capacity(columns.len());
+    for column in columns {
+        validate_identifier(column)?;
+        predicates.push(format!("{} LIKE ? ESCAPE '!'", quote_identifier(column)));
+    }
+    Ok((
+        format!(" WHERE ({})", predicates.join(" OR ")),
+        vec![pattern; columns.len()],
+    ))
+}
+
+/// Renders ` ORDER BY <column> <dir>` for browsing a table. The column must be one of the
+/// table's real columns and the direction `ASC` or `DESC` (any case, default `ASC`), so
+/// neither can smuggle SQL into the query.
+fn build_order_by_clause(
+    table_columns: &[String],
+    column: &str,
+    sort_direction: Option<&str>,
+    quote_identifier: fn(&str) -> String,
+) -> Result<String, String> {
+    if !table_columns.iter().any(|c| c == column) {
+        return Err(format!("Unknown column '{}' in sort", column));
+    }
+    let direction = match sort_direction.map(|d| d.trim().to_uppercase()) {
+        None => "ASC",
+        Some(d) if d.is_empty() || d == "ASC" => "ASC",
+        Some(d) if d == "DESC" => "DESC",
+        Some(d) => {
+            return Err(format!(
+                "Invalid sort direction '{}': expected ASC or DESC",
+                d
+            ))
+        }
+    };
+    Ok(format!(
+        " ORDER BY {} {}",
+        quote_identifier(column),
+        direction
+    ))
+}
+
+/// Rows `column_stats` looks at for the distinct count. On bigger tables only the first this
+/// many rows are counted and the result is flagged approximate.
+const DISTINCT_SAMPLE_ROWS: u64 = 1_000_000;
+
+/// Which aggregates `column_stats` computes for a column, decided from its declared type.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatsKind {
+    /// Gets an average on top of MIN/MAX.
+    Numeric,
+    /// Strings, dates and anything else that orders sensibly.
+    Comparable,
+    /// Binary data, where MIN/MAX mean nothing to a reader.
+    Binary,
+}
+
+/// The single aggregate query behind `column_stats`. Selects, in order: row count, NULL
+/// count, MIN, MAX, AVG and the distinct count over at most `DISTINCT_SAMPLE_ROWS` rows.
+/// Aggregates that don't apply to `kind` select NULL so the positions stay fixed.
+fn build_column_stats_query(
+    table_name: &str,
+    column: &str,
+    kind: StatsKind,
+    quote_identifier: fn(&str) -> String,
+) -> String {
+    let table = quote_identifier(table_name);
+    let col = quote_identifier(column);
+    let (min, max) = match kind {
+        StatsKind::Binary => ("NULL".to_string(), "NULL".to_string()),
+        _ => (format!("MIN({})", col), format!("MAX({})", col)),
+    };
+    let average = match kind {
+        StatsKind::Numeric => format!("AVG({})", col),
+        _ => "NULL".to_string(),
+    };
+    format!(
+        "SELECT COUNT(*), COUNT(*) - COUNT({col}), {min}, {max}, {average}, \
+         (SELECT COUNT(DISTINCT {col}) FROM (SELECT {col} FROM {table} LIMIT {limit}) AS sample) \
+         FROM {table}",
+        col = col,
+        table = table,
+        min = min,
+        max = max,
+        average = average,
+        limit = DISTINCT_SAMPLE_ROWS,
+    )
+}
+
+/// Splits a SQL script into individual statements on `;`, ignoring semicolons inside
+/// quoted strings, quoted identifiers and comments. Backslash escapes inside strings are
+/// honoured as MySQL does. Empty and comment-only statements are dropped.
+pub fn split_sql_statements(sql: &str) -> Vec<String> {
+    split_sql(sql, false)
+}
+
+/// Like `split_sql_statements`, but also honours the MySQL client's `DELIMITER` directive
+/// (e.g. `DELIMITER $$` around trigger and procedure bodies), as found in dump files.
+pub fn split_sql_script(sql: &str) -> Vec<String> {
+    split_sql(sql, true)
+}
+
+fn split_sql(sql: &str, allow_delimiter_directive: bool) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut delimiter = vec![';'];
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut has_content = false;
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        // A directive is only recognised on its own line between statements
+        if allow_delimiter_directive && line_start && !has_content {
+            if let Some((new_delimiter, line_end)) = delimiter_directive(&chars[i..]) {
+                delimiter = new_delimiter;
+                current.clear();
+                i += line_end;
+                continue;
+            }
+        }
+        if chars[i..].starts_with(&delimiter) {
+            if has_content {
+                statements.push(current.trim().to_string());
+            }
+            current.clear();
+            has_content = false;
+            line_start = false;
+            i += delimiter.len();
+            continue;
+        }
+
+        let c = chars[i];
+        i += 1;
+        line_start = c == '\n';
+        match c {
+            '\'' | '"' | '`' => {
+                has_content = true;
+                current.push(c);
+                // Copy through to the closing quote; doubled quotes simply reopen the string
+                while i < chars.len() {
+                    let inner = chars[i];
+                    i += 1;
+                    current.push(inner);
+                    if inner == '\\' && c != '`' {
+                        if let Some(&escaped) = chars.get(i) {
+                            current.push(escaped);
+                            i += 1;
+                        }
+                    } else if inner == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.get(i) == Some(&'-') => {
+                current.push(c);
+                while i < chars.len() {
+                    let inner = chars[i];
+                    i += 1;
+                    current.push(inner);
+                    if inner == '\n' {
+                        line_start = true;
+                        break;
+                    }
+                }
+            }
+            '/' if chars.get(i) == Some(&'*') => {
+                current.push(c);
+                current.push('*');
+                i += 1;
+                let mut prev = '\0';
+                while i < chars.len() {
+                    let inner = chars[i];
+                    i += 1;
+                    current.push(inner);
+                    if prev == '*' && inner == '/' {
+                        break;
+                    }
+                    prev = inner;
+                }
+            }
+            _ => {
+                has_content |= !c.is_whitespace();
+                current.push(c);
+            }
+        }
+    }
+
+    if has_content {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+/// Parses a `DELIMITER <token>` line at the start of `chars`, returning the new delimiter
+/// and the length of the line (excluding its newline).
+fn delimiter_directive(chars: &[char]) -> Option<(Vec<char>, usize)> {
+    let line_end = chars.iter().position(|&c| c == '\n').unwrap_or(chars.len());
+    let line: String = chars[..line_end].iter().collect();
+    let mut parts = line.trim().splitn(2, char::is_whitespace);
+    if !parts.next()?.eq_ignore_ascii_case("DELIMITER") {
+        return None;
+    }
+    let delimiter = parts.next()?.trim();
+    if delimiter.is_empty() {
+        return None;
+    }
+    Some((delimiter.chars().collect(), line_end))
+}
+
+/// Whether a statement begins or ends a transaction itself, as SQLite dumps do.
+pub fn is_transaction_control(statement: &str) -> bool {
+    matches!(
+        first_keyword(statement).as_str(),
+        "BEGIN" | "START" | "COMMIT" | "ROLLBACK" | "END"
+    )
+}
 
 pub trait DbBackend {
+    fn get_databases(&mut self, include_system: bool) -> Result<Vec<String>, String>;
     fn get_tables(&mut self) -> Result<Vec<String>, String>;
+    fn get_foreign_keys(&mut self, table_name: &str) -> Result<Vec<ForeignKey>, String>;
+    fn get_indexes(&mut self, table_name: &str) -> Result<Vec<IndexInfo>, String>;
+    /// Primary key columns in key order; empty when the table has no primary key.
+    fn get_primary_key(&mut self, table_name: &str) -> Result<Vec<String>, String>;
+    /// Column names in table order.
+    fn get_column_names(&mut self, table_name: &str) -> Result<Vec<String>, String>;
+    /// Columns worth searching with `LIKE`: the string types on MySQL. SQLite columns have
+    /// no enforced type, so every column is returned there.
+    fn get_text_columns(&mut self, table_name: &str) -> Result<Vec<String>, String>;
+    /// `sort_column` must name a real column of the table and `sort_direction` be `ASC` or
+    /// `DESC`; anything else is rejected. Rows are unordered when no column is given.
     fn get_table_data(
         &mut self,
         table_name: &str,
         page: u32,
         per_page: u32,
-        where_clause: Option<String>,
+        filter: &RowFilter,
         sort_column: Option<String>,
         sort_direction: Option<String>,
     ) -> Result<TableData, String>;
-    fn execute_query(&mut self, query: &str) -> Result<TableData, String>;
-    fn delete_row(
+    /// Keyset (cursor) pagination: `WHERE pk > after_pk ORDER BY pk LIMIT per_page`.
+    /// Stays fast on large tables where OFFSET scans get slow, but requires a sortable
+    /// primary key column. The returned `next_cursor` is the last PK on the page.
+    fn get_table_data_keyset(
         &mut self,
         table_name: &str,
         pk_column: &str,
-        pk_value: &str,
-    ) -> Result<u64, String>;
+        after_pk: Option<String>,
+        per_page: u32,
+    ) -> Result<TableData, String>;
+    /// Runs one or more `;`-separated statements in order and returns the result of the last.
+    /// With `use_transaction` the whole batch is rolled back if any statement fails.
+    fn execute_query(&mut self, query: &str, use_transaction: bool) -> Result<TableData, String>;
+    /// Deletes the row matching every `(column, value)` pair of a (possibly composite) key.
+    fn delete_row(&mut self, table_name: &str, pk: &[(String, String)]) -> Result<u64, String>;
+    /// With `expected`, the update only applies if the row still holds those values.
     fn update_row(
         &mut self,
         table_name: &str,
-        pk_column: &str,
-        pk_value: &str,
+        pk: &[(String, String)],
+        expected: Option<&HashMap<String, Option<String>>>,
         data: HashMap<String, Option<String>>,
     ) -> Result<u64, String>;
-    fn get_total_rows(
+    /// Exact `COUNT(*)` of the rows matching `filter`. Kept out of `get_table_data` so pages
+    /// load without waiting on a count; the UI requests it separately.
+    fn count_rows(&mut self, table_name: &str, filter: &RowFilter) -> Result<u64, String>;
+    /// Creates a new table, failing if one with the same name already exists.
+    fn create_table(
         &mut self,
         table_name: &str,
-        where_clause: Option<String>,
+        columns: &[ColumnDefinition],
+    ) -> Result<(), String>;
+    fn drop_table(&mut self, table_name: &str) -> Result<(), String>;
+    /// Removes every row from a table and returns how many were deleted.
+    fn truncate_table(&mut self, table_name: &str) -> Result<u64, String>;
+    /// Row, NULL and distinct counts plus MIN/MAX (and AVG for numeric columns) of one column,
+    /// from a single aggregate query. Fails for columns the table doesn't have.
+    fn column_stats(&mut self, table_name: &str, column: &str) -> Result<ColumnStats, String>;
+    /// Cheap row count for large tables. MySQL reads `information_schema.tables.TABLE_ROWS`,
+    /// which for InnoDB is a sampled statistic that can be off by 40-50%; SQLite has no such
+    /// statistic and counts exactly.
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<RowCount, String>;
+    /// The row matching `pk` as a ready-to-run `INSERT` in this backend's dialect, or `None`
+    /// when no row matches.
+    fn row_as_insert(
+        &mut self,
+        table_name: &str,
+        pk: &[(String, String)],
+    ) -> Result<Option<String>, String>;
+    /// Writes a restorable SQL dump of `tables` (DDL followed by batched `INSERT`s) to `out`,
+    /// streaming rows instead of loading whole tables. `on_table` gets each table's name and
+    /// row count once it is written. Returns the total number of rows dumped.
+    fn dump_tables(
+        &mut self,
+        tables: &[String],
+        out: &mut dyn Write,
+        on_table: &mut dyn FnMut(&str, u64),
     ) -> Result<u64, String>;
+    /// Runs already split statements in order, stopping at the first failure. With
+    /// `use_transaction` a failure rolls back everything before it; MySQL still commits
+    /// implicitly on DDL, so scripts with `CREATE`/`ALTER` can only be partly undone.
+    /// Statement errors are reported in the result, not as `Err`.
+    fn run_script(
+        &mut self,
+        statements: &[String],
+        use_transaction: bool,
+    ) -> Result<SqlScriptResult, String>;
+    /// A handle that interrupts this backend's running query from another thread. Taken when
+    /// the connection opens, since the backend itself stays locked while a query runs.
+    fn canceller(&self) -> Option<Arc<dyn QueryCanceller>> {
+        None
+    }
+}
+
+pub trait QueryCanceller: Send + Sync {
+    /// Interrupts the query in flight, if any. Returns whether there was one to cancel.
+    fn cancel(&self) -> Result<bool, String>;
+}
+
+/// Marks a query as in flight in a slot shared with the backend's `QueryCanceller`, and
+/// resets the slot when dropped.
+struct InFlight<T: Default>(Arc<Mutex<T>>);
+
+impl<T: Default> InFlight<T> {
+    fn start(slot: &Arc<Mutex<T>>, value: T) -> Self {
+        if let Ok(mut current) = slot.lock() {
+            *current = value;
+        }
+        Self(Arc::clone(slot))
+    }
+}
+
+impl<T: Default> Drop for InFlight<T> {
+    fn drop(&mut self) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = T::default();
+        }
+    }
+}
+
+/// Operations of key/value stores (Redis), kept apart from the SQL-shaped `DbBackend`.
+/// `database` selects a numbered database and defaults to the one the connection was
+/// configured with.
+pub trait KeyValueBackend {
+    /// The numbered databases, listed where a SQL connection lists its tables.
+    fn databases(&mut self) -> Vec<String>;
+    /// Keys matching a glob-style `pattern`, found with `SCAN` so large keyspaces don't
+    /// block the server. Stops at a fixed limit and flags the result as truncated.
+    fn keys(&mut self, database: Option<i64>, pattern: &str) -> Result<RedisKeys, String>;
+    /// The key's value; large collections are capped.
+    fn get(&mut self, database: Option<i64>, key: &str) -> Result<RedisValue, String>;
+    /// The key's type as Redis names it (`string`, `hash`, ...), or `none` if it doesn't exist.
+    fn key_type(&mut self, database: Option<i64>, key: &str) -> Result<String, String>;
+}
+
+/// An open connection: a SQL database, or a key/value store with its own, much smaller set of
+/// operations.
+pub enum Backend {
+    Sql(Box<dyn DbBackend + Send>),
+    KeyValue(Box<dyn KeyValueBackend + Send>),
 }
 
 pub struct MySqlBackend {
     pool: Pool,
+    /// Server-side id of the pooled connection running a query, for `KILL QUERY`.
+    running: Arc<Mutex<Option<u32>>>,
+}
+
+struct MySqlCanceller {
+    pool: Pool,
+    running: Arc<Mutex<Option<u32>>>,
+}
+
+impl QueryCanceller for MySqlCanceller {
+    /// Kills the statement from a second pooled connection. The slot stays locked meanwhile,
+    /// so the query's connection can't go back to the pool and be reused before the kill lands.
+    fn cancel(&self) -> Result<bool, String> {
+        let running = self.running.lock().map_err(|e| e.to_string())?;
+        let Some(id) = *running else {
+            return Ok(false);
+        };
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.query_drop(format!("KILL QUERY {}", id))
+            .map_err(|e| e.to_string())?;
+        tracing::info!(connection_id = id, "Cancelled running MySQL query");
+        Ok(true)
+    }
 }
 
 impl MySqlBackend {
     pub fn new(creds: &DbCredentials) -> Result<Self, String> {
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(creds.host.clone().unwrap_or_default()))
-            .tcp_port(
-                creds
-                    .port
-                    .clone()
-                    .unwrap_or("3306".to_string())
-                    .parse()
-                    .map_err(|e| format!("Invalid port number: {}", e))?,
-            )
+        let socket = creds.socket.clone().filter(|s| !s.trim().is_empty());
+
+        let mut opts = OptsBuilder::new()
             .db_name(Some(creds.database.clone()))
             .user(Some(creds.username.clone().unwrap_or_default()))
             .pass(Some(creds.password.clone().unwrap_or_default()));
 
-        let pool =
-            Pool::new(opts).map_err(|e| format!("Failed to connect to MySQL database: {}", e))?;
-        Ok(Self { pool })
+        // A socket wins over host/port: stock Homebrew/MAMP installs often only listen on one
+        opts = match &socket {
+            Some(socket) => opts.socket(Some(socket.clone())),
+            None => opts
+                .ip_or_hostname(Some(creds.host.clone().unwrap_or_default()))
+                .tcp_port(
+                    creds
+                        .port
+                        .clone()
+                        .unwrap_or("3306".to_string())
+                        .parse()
+                        .map_err(|e| format!("Invalid port number: {}", e))?,
+                ),
+        };
+
+        let pool = Pool::new(opts).map_err(|e| match &socket {
+            Some(socket) => format!(
+                "Failed to connect to MySQL database via socket {} (the socket is used instead of host/port when set): {}",
+                socket, e
+            ),
+            None => format!("Failed to connect to MySQL database: {}", e),
+        })?;
+        Ok(Self {
+            pool,
+            running: Arc::new(Mutex::new(None)),
+        })
     }
 
-    fn convert_value(value: &MySqlValue) -> Option<String> {
+    /// Registers `conn` as running a cancellable query until the returned guard is dropped.
+    /// Declare the guard after the connection so it is cleared before the connection returns
+    /// to the pool.
+    fn track(&self, conn: &mysql::PooledConn) -> InFlight<Option<u32>> {
+        InFlight::start(&self.running, Some(conn.connection_id()))
+    }
+
+    fn convert_value(value: &MySqlValue, column: &mysql::Column) -> Option<String> {
         match value {
             MySqlValue::NULL => None,
+            // DECIMAL always arrives as the server's text in both protocols; pass it through
+            // untouched so no digits are gained or lost
             MySqlValue::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).to_string()),
             MySqlValue::Int(n) => Some(n.to_string()),
             MySqlValue::UInt(n) => Some(n.to_string()),
-            MySqlValue::Float(n) => Some(n.to_string()),
-            MySqlValue::Double(n) => Some(n.to_string()),
-            MySqlValue::Date(y, m, d, h, i, s, _) => Some(format!(
-                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                y, m, d, h, i, s
+            // Binary rows carry FLOAT/DOUBLE natively. Format them at their own width (no f32 -> f64
+            // widening noise) and pad to the declared scale, as the text protocol does.
+            MySqlValue::Float(n) => Some(match Self::fixed_scale(column) {
+                Some(scale) => format!("{:.*}", scale, n),
+                None => n.to_string(),
+            }),
+            MySqlValue::Double(n) => Some(match Self::fixed_scale(column) {
+                Some(scale) => format!("{:.*}", scale, n),
+                None => n.to_string(),
+            }),
+            // Prepared statements return dates in binary form; render them the way the text
+            // protocol would so values look the same whether or not a query was parameterized
+            MySqlValue::Date(y, m, d, ..)
+                if matches!(
+                    column.column_type(),
+                    ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE
+                ) =>
+            {
+                Some(format!("{:04}-{:02}-{:02}", y, m, d))
+            }
+            MySqlValue::Date(y, m, d, h, i, s, micros) => Some(format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}{}",
+                y,
+                m,
+                d,
+                h,
+                i,
+                s,
+                Self::fractional_seconds(*micros, column)
             )),
-            MySqlValue::Time(neg, d, h, i, s, _) => {
+            // TIME is an interval (-838:59:59 to 838:59:59), so days fold into the hour
+            // count the way the mysql client shows it
+            MySqlValue::Time(neg, d, h, i, s, micros) => {
                 let sign = if *neg { "-" } else { "" };
-                Some(format!("{}{}.{:02}:{:02}:{:02}", sign, d, h, i, s))
+                let hours = *d as u64 * 24 + *h as u64;
+                Some(format!(
+                    "{}{:02}:{:02}:{:02}{}",
+                    sign,
+                    hours,
+                    i,
+                    s,
+                    Self::fractional_seconds(*micros, column)
+                ))
             }
         }
     }
 
+    /// Rows matched by the last UPDATE. affected_rows() only counts rows that actually changed,
+    /// so an edit that writes identical values would otherwise look like a conflict.
+    fn matched_rows(conn: &mysql::Conn) -> u64 {
+        conn.info_str()
+            .strip_prefix("Rows matched: ")
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|n| n.parse().ok())
+            .unwrap_or_else(|| conn.affected_rows())
+    }
+
+    /// Declared scale of a FLOAT(M,D)/DOUBLE(M,D) column. 31 means the column has none.
+    fn fixed_scale(column: &mysql::Column) -> Option<usize> {
+        match column.decimals() {
+            decimals if decimals < 31 => Some(decimals as usize),
+            _ => None,
+        }
+    }
+
+    /// Fractional seconds padded to the column's declared precision, e.g. ".120" for DATETIME(3).
+    fn fractional_seconds(micros: u32, column: &mysql::Column) -> String {
+        let decimals = column.decimals() as usize;
+        if (1..=6).contains(&decimals) {
+            format!(".{}", &format!("{:06}", micros)[..decimals])
+        } else {
+            String::new()
+        }
+    }
+
     fn map_mysql_type_to_string(t: ColumnType) -> String {
         format!("{:?}", t)
     }
+
+    fn quote_identifier(name: &str) -> String {
+        format!("`{}`", name.replace('`', "``"))
+    }
+
+    fn quote_literal(value: &str) -> String {
+        MySqlValue::from(value).as_sql(false)
+    }
+
+    /// Drains the current result set. Columns come from the result set header rather than
+    /// the first row, so they are known even when nothing matched.
+    fn fetch_result_set<T: Protocol>(
+        result: &mut QueryResult<'_, '_, '_, T>,
+    ) -> Result<(Vec<mysql::Column>, Vec<mysql::Row>), String> {
+        let columns = result.columns().as_ref().to_vec();
+        let rows = result
+            .by_ref()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok((columns, rows))
+    }
+
+    /// Executes statements in order, returning the result set and affected row count of the
+    /// last one.
+    fn run_statements<Q: Queryable>(
+        conn: &mut Q,
+        statements: &[String],
+    ) -> Result<(Vec<mysql::Column>, Vec<mysql::Row>, u64), String> {
+        let mut result_set = (Vec::new(), Vec::new());
+        let mut affected_rows = 0;
+        for (index, statement) in statements.iter().enumerate() {
+            let fail = |e: String| format!("Statement {} failed: {}", index + 1, e);
+            let mut result = conn
+                .query_iter(statement)
+                .map_err(|e| fail(e.to_string()))?;
+            affected_rows = result.affected_rows();
+            result_set = Self::fetch_result_set(&mut result).map_err(fail)?;
+        }
+        let (columns, rows) = result_set;
+        Ok((columns, rows, affected_rows))
+    }
+
+    /// Executes script statements until one fails; results are discarded.
+    fn run_script_statements<Q: Queryable>(conn: &mut Q, statements: &[String]) -> SqlScriptResult {
+        for (index, statement) in statements.iter().enumerate() {
+            if let Err(e) = conn.query_drop(statement) {
+                return SqlScriptResult {
+                    total: statements.len(),
+                    executed: index,
+                    failed: Some(FailedStatement {
+                        index: index + 1,
+                        statement: statement.clone(),
+                        error: e.to_string(),
+                    }),
+                    rolled_back: false,
+                };
+            }
+        }
+        SqlScriptResult {
+            total: statements.len(),
+            executed: statements.len(),
+            failed: None,
+            rolled_back: false,
+        }
+    }
+
+    /// Splits a result set into column names, column metadata and stringified rows.
+    fn collect_rows(
+        result_columns: &[mysql::Column],
+        rows: Vec<mysql::Row>,
+    ) -> (Vec<String>, Vec<ColumnDetail>, Vec<RowData>) {
+        let mut columns = Vec::new();
+        let mut column_details = Vec::new();
+
+        for col in result_columns {
+            columns.push(col.name_str().to_string());
+            column_details.push(ColumnDetail {
+                name: col.name_str().to_string(),
+                data_type: Self::map_mysql_type_to_string(col.column_type()),
+                is_nullable: !col
+                    .flags()
+                    .contains(mysql::consts::ColumnFlags::NOT_NULL_FLAG),
+                default_value: None, // Difficult to get from result set metadata
+                // Laravel's boolean() columns are tinyint(1), reported with a display length of 1
+                is_boolean: col.column_type() == ColumnType::MYSQL_TYPE_TINY
+                    && col.column_length() == 1,
+            });
+        }
+
+        let mut data = Vec::new();
+        for row in rows {
+            let mut row_data = HashMap::new();
+            for (i, column) in columns.iter().enumerate() {
+                row_data.insert(
+                    column.clone(),
+                    Self::convert_value(&row[i], &result_columns[i]),
+                );
+            }
+            data.push(row_data);
+        }
+
+        (columns, column_details, data)
+    }
 }
 
 impl DbBackend for MySqlBackend {
+    fn canceller(&self) -> Option<Arc<dyn QueryCanceller>> {
+        Some(Arc::new(MySqlCanceller {
+            pool: self.pool.clone(),
+            running: Arc::clone(&self.running),
+        }))
+    }
+
+    fn get_databases(&mut self, include_system: bool) -> Result<Vec<String>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let databases: Vec<String> = conn.query("SHOW DATABASES").map_err(|e| e.to_string())?;
+
+        Ok(databases
+            .into_iter()
+            .filter(|db| include_system || !MYSQL_SYSTEM_SCHEMAS.contains(&db.as_str()))
+            .collect())
+    }
+
     fn get_tables(&mut self) -> Result<Vec<String>, String> {
         let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
         conn.query_map("SHOW TABLES", |table_name: String| table_name)
             .map_err(|e| e.to_string())
     }
 
+    fn get_foreign_keys(&mut self, table_name: &str) -> Result<Vec<ForeignKey>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.exec_map(
+            "SELECT CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME
+             FROM information_schema.KEY_COLUMN_USAGE
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND REFERENCED_TABLE_NAME IS NOT NULL
+             ORDER BY CONSTRAINT_NAME, ORDINAL_POSITION",
+            (table_name,),
+            |(constraint_name, column, referenced_table, referenced_column): (
+                String,
+                String,
+                String,
+                String,
+            )| ForeignKey {
+                constraint_name,
+                column,
+                referenced_table,
+                referenced_column,
+            },
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn get_indexes(&mut self, table_name: &str) -> Result<Vec<IndexInfo>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let query = format!("SHOW INDEX FROM {}", Self::quote_identifier(table_name));
+        let rows: Vec<mysql::Row> = conn.query(query).map_err(|e| e.to_string())?;
+
+        // Rows arrive ordered by index and then by Seq_in_index, one row per indexed column
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for row in rows {
+            let name: String = row.get("Key_name").unwrap_or_default();
+            let non_unique: i64 = row.get("Non_unique").unwrap_or(1);
+            // Functional indexes (MySQL 8+) have no column name
+            let column: Option<String> = row.get::<Option<String>, _>("Column_name").flatten();
+
+            if let Some(index) = indexes.iter_mut().find(|i| i.name == name) {
+                index.columns.extend(column);
+            } else {
+                indexes.push(IndexInfo {
+                    is_primary: name == "PRIMARY",
+                    is_unique: non_unique == 0,
+                    columns: column.into_iter().collect(),
+                    name,
+                });
+            }
+        }
+        Ok(indexes)
+    }
+
+    fn get_primary_key(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.exec(
+            "SELECT COLUMN_NAME
+             FROM information_schema.KEY_COLUMN_USAGE
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND CONSTRAINT_NAME = 'PRIMARY'
+             ORDER BY ORDINAL_POSITION",
+            (table_name,),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn get_column_names(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.exec(
+            "SELECT COLUMN_NAME
+             FROM information_schema.COLUMNS
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+             ORDER BY ORDINAL_POSITION",
+            (table_name,),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn get_text_columns(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.exec(
+            "SELECT COLUMN_NAME
+             FROM information_schema.COLUMNS
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+               AND DATA_TYPE IN ('char', 'varchar', 'tinytext', 'text', 'mediumtext', 'longtext', 'enum', 'set', 'json')
+             ORDER BY ORDINAL_POSITION",
+            (table_name,),
+        )
+        .map_err(|e| e.to_string())
+    }
+
     fn get_table_data(
         &mut self,
         table_name: &str,
         page: u32,
         per_page: u32,
-        where_clause: Option<String>,
+        filter: &RowFilter,
         sort_column: Option<String>,
         sort_direction: Option<String>,
     ) -> Result<TableData, String> {
         let start = std::time::Instant::now();
-        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
 
-        // This logic mimics the original implementation
-        let mut where_clause_for_select = String::new();
         let limit = per_page;
-        let mut has_limit_in_where = false;
-
-        if let Some(clause) = where_clause {
-            if !clause.trim().is_empty() {
-                let upper_clause = clause.to_uppercase();
-                if upper_clause.rfind("LIMIT").is_some() {
-                    where_clause_for_select = format!(" WHERE {}", clause);
-                    has_limit_in_where = true;
-                } else {
-                    where_clause_for_select = format!(" WHERE {}", clause);
-                }
-            }
-        }
-
-        let offset = (page - 1) * limit;
+        let (where_clause_for_select, params) = build_where_clause(filter, Self::quote_identifier)?;
+        let offset = page_offset(page, limit)?;
 
         // Sorting
-        let mut order_by_clause = String::new();
-        if let Some(col) = sort_column {
-            if !col.trim().is_empty() {
-                // Determine direction
-                let dir = sort_direction
-                    .map(|d| d.to_uppercase())
-                    .unwrap_or_else(|| "ASC".to_string());
-                let dir = if dir == "DESC" { "DESC" } else { "ASC" };
-
-                // Sanitize column name (basic check or quoting)
-                // Using backticks for MySQL
-                order_by_clause = format!(" ORDER BY `{}` {}", col.replace("`", "``"), dir);
-            }
-        }
-
-        // Data
-        // We fetch one more row than requested to determine if there are more pages
-        let query = if has_limit_in_where {
-            format!("SELECT * FROM {}{}", table_name, where_clause_for_select)
-        } else {
-            format!(
-                "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
-                table_name,
-                where_clause_for_select,
-                order_by_clause,
-                limit + 1,
-                offset
-            )
+        let order_by_clause = match sort_column.as_deref() {
+            Some(col) if !col.trim().is_empty() => build_order_by_clause(
+                &self.get_column_names(table_name)?,
+                col,
+                sort_direction.as_deref(),
+                Self::quote_identifier,
+            )?,
+            _ => String::new(),
         };
 
-        let rows: Vec<mysql::Row> = conn.query(query).map_err(|e| e.to_string())?;
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
 
-        let mut data = Vec::new();
-        let mut columns = Vec::new();
-        let mut column_details = Vec::new();
+        // Data
+        // We fetch one more row than requested to determine if there are more pages
+        let query = format!(
+            "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
+            Self::quote_identifier(table_name),
+            where_clause_for_select,
+            order_by_clause,
+            limit as u64 + 1,
+            offset
+        );
 
-        if let Some(first_row) = rows.first() {
-            let row_columns = first_row.columns();
-            for col in row_columns.iter() {
-                columns.push(col.name_str().to_string());
-                column_details.push(ColumnDetail {
-                    name: col.name_str().to_string(),
-                    data_type: Self::map_mysql_type_to_string(col.column_type()),
-                    is_nullable: !col
-                        .flags()
-                        .contains(mysql::consts::ColumnFlags::NOT_NULL_FLAG),
-                    default_value: None, // Difficult to get from result set metadata
-                });
-            }
-        }
+        let mut result = conn.exec_iter(query, params).map_err(|e| e.to_string())?;
+        let (result_columns, rows) = Self::fetch_result_set(&mut result)?;
 
-        for row in rows {
-            let mut row_data = HashMap::new();
-            for (i, column) in columns.iter().enumerate() {
-                row_data.insert(column.clone(), Self::convert_value(&row[i]));
-            }
-            data.push(row_data);
-        }
+        let (columns, column_details, mut data) = Self::collect_rows(&result_columns, rows);
 
         let mut has_more = false;
         if data.len() > limit as usize {
@@ -191,171 +1124,484 @@ impl DbBackend for MySqlBackend {
         }
 
         Ok(TableData {
-            total: 0, // Deprecated/Unused
+            // No COUNT(*) here: on a big table it can take longer than the page itself, so
+            // the UI shows the page first and asks `count_rows` for the total separately
+            total: 0,
             has_more,
             columns,
             column_details,
             rows: data,
             execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor: None,
+            affected_rows: None,
         })
     }
 
-    fn execute_query(&mut self, query: &str) -> Result<TableData, String> {
+    fn get_table_data_keyset(
+        &mut self,
+        table_name: &str,
+        pk_column: &str,
+        after_pk: Option<String>,
+        per_page: u32,
+    ) -> Result<TableData, String> {
         let start = std::time::Instant::now();
         let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
-        let rows: Vec<mysql::Row> = conn.query(query).map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
+
+        let table = Self::quote_identifier(table_name);
+        let pk = Self::quote_identifier(pk_column);
+        let limit = per_page as u64 + 1; // One extra row to determine has_more
+
+        let mut result = match after_pk {
+            Some(after) => conn.exec_iter(
+                format!("SELECT * FROM {table} WHERE {pk} > ? ORDER BY {pk} LIMIT {limit}"),
+                (after,),
+            ),
+            None => conn.exec_iter(
+                format!("SELECT * FROM {table} ORDER BY {pk} LIMIT {limit}"),
+                (),
+            ),
+        }
+        .map_err(|e| e.to_string())?;
+        let (result_columns, rows) = Self::fetch_result_set(&mut result)?;
 
-        let mut data = Vec::new();
-        let mut columns = Vec::new();
-        let mut column_details = Vec::new();
+        let (columns, column_details, mut data) = Self::collect_rows(&result_columns, rows);
 
-        if let Some(first_row) = rows.first() {
-            let row_columns = first_row.columns();
-            for col in row_columns.iter() {
-                columns.push(col.name_str().to_string());
-                column_details.push(ColumnDetail {
-                    name: col.name_str().to_string(),
-                    data_type: Self::map_mysql_type_to_string(col.column_type()),
-                    is_nullable: !col
-                        .flags()
-                        .contains(mysql::consts::ColumnFlags::NOT_NULL_FLAG),
-                    default_value: None,
-                });
-            }
+        let mut has_more = false;
+        if data.len() > per_page as usize {
+            data.pop();
+            has_more = true;
         }
+        let next_cursor = if has_more {
+            data.last()
+                .and_then(|row| row.get(pk_column).cloned().flatten())
+        } else {
+            None
+        };
 
-        for row in rows {
-            let mut row_data = HashMap::new();
-            for (i, column) in columns.iter().enumerate() {
-                row_data.insert(column.clone(), Self::convert_value(&row[i]));
-            }
-            data.push(row_data);
+        Ok(TableData {
+            total: 0,
+            has_more,
+            columns,
+            column_details,
+            rows: data,
+            execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor,
+            affected_rows: None,
+        })
+    }
+
+    fn execute_query(&mut self, query: &str, use_transaction: bool) -> Result<TableData, String> {
+        let start = std::time::Instant::now();
+        let statements = split_sql_statements(query);
+        if statements.is_empty() {
+            return Err("Query is empty".to_string());
         }
 
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
+        let (result_columns, rows, affected_rows) = if use_transaction {
+            // Dropping the transaction without committing rolls it back
+            let mut tx = conn
+                .start_transaction(TxOpts::default())
+                .map_err(|e| e.to_string())?;
+            let result = Self::run_statements(&mut tx, &statements)?;
+            tx.commit().map_err(|e| e.to_string())?;
+            result
+        } else {
+            Self::run_statements(&mut conn, &statements)?
+        };
+
+        let (columns, column_details, data) = Self::collect_rows(&result_columns, rows);
+        let affected_rows = match statements.last() {
+            Some(last) if !returns_rows(last) => Some(affected_rows),
+            _ => None,
+        };
+
         Ok(TableData {
-            total: data.len() as u32,
+            total: data.len() as u64,
             has_more: false,
             columns,
             column_details,
             rows: data,
             execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor: None,
+            affected_rows,
         })
     }
 
-    fn delete_row(
-        &mut self,
-        table_name: &str,
-        pk_column: &str,
-        pk_value: &str,
-    ) -> Result<u64, String> {
-        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+    fn delete_row(&mut self, table_name: &str, pk: &[(String, String)]) -> Result<u64, String> {
+        let (predicate, pk_values) = build_pk_predicate(pk, Self::quote_identifier)?;
         let stmt = format!(
-            "DELETE FROM `{}` WHERE `{}` = :value",
-            table_name, pk_column
+            "DELETE FROM {} WHERE {}",
+            Self::quote_identifier(table_name),
+            predicate
         );
-        conn.exec_drop(stmt, params! {"value" => pk_value})
-            .map_err(|e| e.to_string())?;
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.exec_drop(stmt, pk_values).map_err(|e| e.to_string())?;
         Ok(conn.affected_rows())
     }
 
     fn update_row(
         &mut self,
         table_name: &str,
-        pk_column: &str,
-        pk_value: &str,
+        pk: &[(String, String)],
+        expected: Option<&HashMap<String, Option<String>>>,
         data: HashMap<String, Option<String>>,
     ) -> Result<u64, String> {
-        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let (mut predicate, mut where_values) = build_pk_predicate(pk, Self::quote_identifier)?;
+        if let Some(expected) = expected {
+            let (conditions, values) = build_expected_predicate(expected, Self::quote_identifier)?;
+            predicate.push_str(&conditions);
+            where_values.extend(values);
+        }
         let mut sets = Vec::new();
         let mut params: Vec<MySqlValue> = Vec::new();
 
         for (key, value) in &data {
-            if key != pk_column {
-                sets.push(format!("`{}` = ?", key));
+            if !pk.iter().any(|(pk_column, _)| pk_column == key) {
+                sets.push(format!("{} = ?", Self::quote_identifier(key)));
                 match value {
                     Some(v) => params.push(MySqlValue::from(v)),
                     None => params.push(MySqlValue::NULL),
                 }
             }
         }
-        params.push(MySqlValue::from(pk_value));
+        if sets.is_empty() {
+            return Err("No columns to update".to_string());
+        }
+        params.extend(where_values.into_iter().map(MySqlValue::from));
 
         let query = format!(
-            "UPDATE `{}` SET {} WHERE `{}` = ?",
-            table_name,
+            "UPDATE {} SET {} WHERE {}",
+            Self::quote_identifier(table_name),
             sets.join(", "),
-            pk_column
+            predicate
         );
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
         conn.exec_drop(query, params).map_err(|e| e.to_string())?;
+        if expected.is_some() && Self::matched_rows(&conn) == 0 {
+            return Err(ROW_MODIFIED_ERROR.to_string());
+        }
         Ok(conn.affected_rows())
     }
 
-    fn get_total_rows(
+    fn count_rows(&mut self, table_name: &str, filter: &RowFilter) -> Result<u64, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
+        let (query, params) = build_count_query(table_name, filter, Self::quote_identifier)?;
+        let count: Option<u64> = conn.exec_first(query, params).map_err(|e| e.to_string())?;
+
+        Ok(count.unwrap_or(0))
+    }
+
+    fn create_table(
         &mut self,
         table_name: &str,
-        where_clause: Option<String>,
-    ) -> Result<u64, String> {
+        columns: &[ColumnDefinition],
+    ) -> Result<(), String> {
+        if self.get_tables()?.iter().any(|t| t == table_name) {
+            return Err(format!("Table '{}' already exists", table_name));
+        }
+        let query = render_create_table(
+            table_name,
+            columns,
+            Self::quote_identifier,
+            Self::quote_literal,
+        )?;
         let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
-        let mut where_clause_for_count = String::new();
-
-        if let Some(clause) = where_clause {
-            if !clause.trim().is_empty() {
-                // Remove LIMIT if present for count query, though usually count queries don't have limit unless subquery
-                // Simple approach: just append WHERE
-                // Ideally we should strip LIMIT/OFFSET from where_clause if it was just passed raw
-                // But typically where_clause passed here shouldn't contain LIMIT for a COUNT(*)
-                // However, the frontend passes the same specific "where input"
-                // The frontend "whereClause" input is just the condition "ID > 5" usually.
-                // But the `get_table_data` implementation checks for "LIMIT" keyword in the where input.
-                // We should probably do the same check to be safe, or just assume COUNT ignores it or it's valid SQL.
-                // MySQL: SELECT COUNT(*) FROM table WHERE ... LIMIT ... is valid but LIMIT applies to the count result (1 row).
-                // If the user typed "LIMIT 5" in the filter box, they might mean "Show me only 5 rows".
-                // If they check total, they might expect the total matching that filter?
-                // If filter is "id > 0 LIMIT 5", total matching is 5?
-                // Let's stick to the same logic as get_table_data for building the WHERE clause.
-
-                // Actually, if the user manually typed "LIMIT 10" in the filter box:
-                // SELECT * FROM table WHERE LIMIT 10 -> Syntax error.
-                // The user is expected to type "id > 5" or "name LIKE 'x%'".
-                // get_table_data handles "col = val LIMIT 5".
-                // Let's copy the logic roughly: use the whole clause.
-                // If it contains LIMIT, we keep it. SELECT COUNT(*) ... WHERE ... LIMIT 5 returns 1 row (the count).
-                // Wait, "WHERE id > 5 LIMIT 10". SELECT COUNT(*) FROM table WHERE id > 5 LIMIT 10.
-                // This returns the count of rows where id > 5. The LIMIT 10 applies to the result set of COUNT(*), which is 1 row.
-                // So LIMIT has no effect on the count value itself in MySQL for a simple count.
-                // UNLESS the user mistakenly types "LIMIT 5" as the *entire* clause and we prepend WHERE.
-                // "SELECT * FROM table WHERE LIMIT 5" -> Error.
-                // The get_table_data code does:
-                // if upper_clause.rfind("LIMIT").is_some() { where_clause_for_select = format!(" WHERE {}", clause); }
-                // else { where_clause_for_select = format!(" WHERE {}", clause); }
-                // It treats them the same?
-                // Ah, line 108 vs 111 in get_table_data: both do `format!(" WHERE {}", clause)`.
-                // The only difference is `has_limit_in_where = true`.
-                // So we can just prepend WHERE if not empty.
-
-                where_clause_for_count = format!(" WHERE {}", clause);
-            }
+        conn.query_drop(query).map_err(|e| e.to_string())
+    }
+
+    fn drop_table(&mut self, table_name: &str) -> Result<(), String> {
+        validate_identifier(table_name)?;
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        conn.query_drop(format!("DROP TABLE {}", Self::quote_identifier(table_name)))
+            .map_err(|e| e.to_string())
+    }
+
+    fn truncate_table(&mut self, table_name: &str) -> Result<u64, String> {
+        validate_identifier(table_name)?;
+        let table = Self::quote_identifier(table_name);
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        // TRUNCATE always reports 0 affected rows, so count them beforehand.
+        let count: Option<u64> = conn
+            .query_first(format!("SELECT COUNT(*) FROM {}", table))
+            .map_err(|e| e.to_string())?;
+        conn.query_drop(format!("TRUNCATE TABLE {}", table))
+            .map_err(|e| e.to_string())?;
+        Ok(count.unwrap_or(0))
+    }
+
+    fn column_stats(&mut self, table_name: &str, column: &str) -> Result<ColumnStats, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let data_type: Option<String> = conn
+            .exec_first(
+                "SELECT DATA_TYPE FROM information_schema.COLUMNS
+                 WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND COLUMN_NAME = ?",
+                (table_name, column),
+            )
+            .map_err(|e| e.to_string())?;
+        let data_type = data_type
+            .ok_or_else(|| format!("Unknown column '{}' in table '{}'", column, table_name))?;
+        let kind = match data_type.to_lowercase().as_str() {
+            "tinyint" | "smallint" | "mediumint" | "int" | "bigint" | "decimal" | "float"
+            | "double" => StatsKind::Numeric,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit"
+            | "geometry" | "point" | "linestring" | "polygon" | "multipoint"
+            | "multilinestring" | "multipolygon" | "geometrycollection" => StatsKind::Binary,
+            _ => StatsKind::Comparable,
+        };
+
+        let query = build_column_stats_query(table_name, column, kind, Self::quote_identifier);
+        let _running = self.track(&conn);
+        let row: mysql::Row = conn
+            .query_first(query)
+            .map_err(|e| e.to_string())?
+            .ok_or("Aggregate query returned no row")?;
+        let text = |i: usize| {
+            row.as_ref(i)
+                .and_then(|value| Self::convert_value(value, &row.columns_ref()[i]))
+        };
+        let count = |i: usize| text(i).and_then(|n| n.parse().ok()).unwrap_or(0);
+
+        let rows = count(0);
+        Ok(ColumnStats {
+            column: column.to_string(),
+            data_type,
+            rows,
+            null_count: count(1),
+            distinct_count: count(5),
+            distinct_approximate: rows > DISTINCT_SAMPLE_ROWS,
+            min: text(2),
+            max: text(3),
+            average: text(4).and_then(|avg| avg.parse().ok()),
+        })
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<RowCount, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let estimate: Option<Option<u64>> = conn
+            .exec_first(
+                "SELECT TABLE_ROWS FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = ?",
+                (table_name,),
+            )
+            .map_err(|e| e.to_string())?;
+        match estimate.flatten() {
+            Some(count) => Ok(RowCount {
+                count,
+                estimated: true,
+            }),
+            // Views have no TABLE_ROWS
+            None => Ok(RowCount {
+                count: self.count_rows(table_name, &RowFilter::Columns(Vec::new()))?,
+                estimated: false,
+            }),
         }
+    }
 
+    fn row_as_insert(
+        &mut self,
+        table_name: &str,
+        pk: &[(String, String)],
+    ) -> Result<Option<String>, String> {
+        let (predicate, pk_values) = build_pk_predicate(pk, Self::quote_identifier)?;
         let query = format!(
-            "SELECT COUNT(*) FROM {}{}",
-            table_name, where_clause_for_count
+            "SELECT * FROM {} WHERE {} LIMIT 1",
+            Self::quote_identifier(table_name),
+            predicate
         );
-        let count: Option<u64> = conn.query_first(query).map_err(|e| e.to_string())?;
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let mut result = conn
+            .exec_iter(query, pk_values)
+            .map_err(|e| e.to_string())?;
+        let (columns, rows) = Self::fetch_result_set(&mut result)?;
+        let row = match rows.into_iter().next() {
+            Some(row) => row,
+            None => return Ok(None),
+        };
 
-        Ok(count.unwrap_or(0))
+        let names: Vec<String> = columns.iter().map(|c| c.name_str().to_string()).collect();
+        // as_sql renders NULL, numbers, dates and escaped strings the way MySQL reads them back
+        let values: Vec<String> = (0..columns.len())
+            .map(|i| {
+                row.as_ref(i)
+                    .map(|value| value.as_sql(false))
+                    .unwrap_or_else(|| "NULL".to_string())
+            })
+            .collect();
+        Ok(Some(render_insert(
+            table_name,
+            &names,
+            &[values],
+            Self::quote_identifier,
+        )))
+    }
+
+    fn dump_tables(
+        &mut self,
+        tables: &[String],
+        out: &mut dyn Write,
+        on_table: &mut dyn FnMut(&str, u64),
+    ) -> Result<u64, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
+        writeln!(out, "SET NAMES utf8mb4;\nSET FOREIGN_KEY_CHECKS = 0;\n")
+            .map_err(|e| e.to_string())?;
+
+        // Views may select from any table, so they are created once all tables exist
+        let mut views = Vec::new();
+        let mut total = 0;
+        for table in tables {
+            let quoted = Self::quote_identifier(table);
+            let create: mysql::Row = conn
+                .query_first(format!("SHOW CREATE TABLE {}", quoted))
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Table '{}' not found", table))?;
+            let is_view = create
+                .columns_ref()
+                .first()
+                .is_some_and(|column| column.name_str() == "View");
+            let ddl: String = create
+                .get_opt(1)
+                .and_then(|ddl| ddl.ok())
+                .ok_or_else(|| format!("No definition returned for '{}'", table))?;
+            if is_view {
+                views.push((table, ddl));
+                continue;
+            }
+            writeln!(out, "DROP TABLE IF EXISTS {};\n{};\n", quoted, ddl)
+                .map_err(|e| e.to_string())?;
+
+            let mut result = conn
+                .query_iter(format!("SELECT * FROM {}", quoted))
+                .map_err(|e| e.to_string())?;
+            let columns: Vec<String> = result
+                .columns()
+                .as_ref()
+                .iter()
+                .map(|c| c.name_str().to_string())
+                .collect();
+            let rows = result.by_ref().map(|row| {
+                let row = row.map_err(|e| e.to_string())?;
+                Ok((0..row.len())
+                    .map(|i| {
+                        row.as_ref(i)
+                            .map(|value| value.as_sql(false))
+                            .unwrap_or_else(|| "NULL".to_string())
+                    })
+                    .collect())
+            });
+            let count = write_insert_batches(out, table, &columns, rows, Self::quote_identifier)?;
+            writeln!(out).map_err(|e| e.to_string())?;
+            on_table(table, count);
+            total += count;
+        }
+        for (view, ddl) in views {
+            writeln!(
+                out,
+                "DROP VIEW IF EXISTS {};\n{};\n",
+                Self::quote_identifier(view),
+                ddl
+            )
+            .map_err(|e| e.to_string())?;
+            on_table(view, 0);
+        }
+
+        writeln!(out, "SET FOREIGN_KEY_CHECKS = 1;").map_err(|e| e.to_string())?;
+        Ok(total)
+    }
+
+    fn run_script(
+        &mut self,
+        statements: &[String],
+        use_transaction: bool,
+    ) -> Result<SqlScriptResult, String> {
+        let mut conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        let _running = self.track(&conn);
+        if !use_transaction {
+            return Ok(Self::run_script_statements(&mut conn, statements));
+        }
+
+        // Dropping the transaction without committing rolls it back
+        let mut tx = conn
+            .start_transaction(TxOpts::default())
+            .map_err(|e| e.to_string())?;
+        let mut result = Self::run_script_statements(&mut tx, statements);
+        if result.failed.is_none() {
+            tx.commit().map_err(|e| e.to_string())?;
+        } else {
+            result.rolled_back = true;
+        }
+        Ok(result)
     }
 }
 
 pub struct SqliteBackend {
     conn: Connection,
+    path: String,
+    running: Arc<Mutex<bool>>,
+}
+
+struct SqliteCanceller {
+    handle: rusqlite::InterruptHandle,
+    running: Arc<Mutex<bool>>,
+}
+
+impl QueryCanceller for SqliteCanceller {
+    fn cancel(&self) -> Result<bool, String> {
+        let running = self.running.lock().map_err(|e| e.to_string())?;
+        if *running {
+            self.handle.interrupt();
+            tracing::info!("Cancelled running SQLite query");
+        }
+        Ok(*running)
+    }
 }
 
 impl SqliteBackend {
-    pub fn new(path: &str) -> Result<Self, String> {
+    pub fn new(path: &str, creds: &DbCredentials) -> Result<Self, String> {
         let conn = Connection::open(path)
             .map_err(|e| format!("Failed to open SQLite database at {}: {}", path, e))?;
-        Ok(Self { conn })
+        let backend = Self {
+            conn,
+            path: path.to_string(),
+            running: Arc::new(Mutex::new(false)),
+        };
+        backend.apply_pragmas(creds)?;
+        Ok(backend)
+    }
+
+    /// WAL lets us read while a running app writes. Pragmas are best-effort so a read-only
+    /// file (where WAL can't be enabled) still opens; only invalid settings are an error.
+    fn apply_pragmas(&self, creds: &DbCredentials) -> Result<(), String> {
+        let synchronous = match creds.synchronous.as_deref().map(str::to_uppercase) {
+            Some(mode) if ["OFF", "NORMAL", "FULL", "EXTRA"].contains(&mode.as_str()) => Some(mode),
+            Some(mode) => return Err(format!("Invalid SQLite synchronous mode '{}'", mode)),
+            None => None,
+        };
+
+        // journal_mode returns the resulting mode as a row, so it can't go through execute
+        let _ = self
+            .conn
+            .query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()));
+        let foreign_keys = if creds.foreign_keys.unwrap_or(true) {
+            "ON"
+        } else {
+            "OFF"
+        };
+        let _ = self
+            .conn
+            .execute_batch(&format!("PRAGMA foreign_keys = {}", foreign_keys));
+        if let Some(mode) = synchronous {
+            let _ = self
+                .conn
+                .execute_batch(&format!("PRAGMA synchronous = {}", mode));
+        }
+        if let Some(ms) = creds.busy_timeout {
+            let _ = self.conn.busy_timeout(std::time::Duration::from_millis(ms));
+        }
+        Ok(())
     }
 
     fn convert_value(value: SqliteValue) -> Option<String> {
@@ -367,9 +1613,142 @@ impl SqliteBackend {
             SqliteValue::Blob(b) => Some(String::from_utf8_lossy(&b).to_string()),
         }
     }
+
+    fn quote_identifier(name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    fn quote_literal(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// Renders a stored value as a literal that SQLite reads back unchanged.
+    fn sql_literal(value: SqliteValue) -> String {
+        match value {
+            SqliteValue::Null => "NULL".to_string(),
+            SqliteValue::Integer(i) => i.to_string(),
+            // SQLite has no infinity literal; 1e999 overflows to it, as `.dump` does
+            SqliteValue::Real(f) if f.is_infinite() => {
+                if f > 0.0 { "1e999" } else { "-1e999" }.to_string()
+            }
+            SqliteValue::Real(f) => format!("{:?}", f),
+            SqliteValue::Text(s) => Self::quote_literal(&s),
+            SqliteValue::Blob(b) => {
+                let hex: String = b.iter().map(|byte| format!("{:02X}", byte)).collect();
+                format!("X'{}'", hex)
+            }
+        }
+    }
+
+    /// Runs a single statement, stepping through and discarding any rows it returns.
+    fn run_statement(&self, statement: &str) -> Result<(), String> {
+        let mut stmt = self.conn.prepare(statement).map_err(|e| e.to_string())?;
+        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+        while rows.next().map_err(|e| e.to_string())?.is_some() {}
+        Ok(())
+    }
+
+    /// Runs a query and returns its column names along with the stringified rows.
+    fn collect_rows<P: rusqlite::Params>(
+        &self,
+        query: &str,
+        params: P,
+    ) -> Result<(Vec<String>, Vec<RowData>), String> {
+        let mut stmt = self.conn.prepare(query).map_err(|e| e.to_string())?;
+
+        let columns: Vec<String> = stmt
+            .column_names()
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let rows = stmt
+            .query_map(params, |row| {
+                let mut map = HashMap::new();
+                for (i, name) in columns.iter().enumerate() {
+                    let val: SqliteValue = row.get(i)?;
+                    map.insert(name.clone(), Self::convert_value(val));
+                }
+                Ok(map)
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut data = Vec::new();
+        for row in rows {
+            data.push(row.map_err(|e| e.to_string())?);
+        }
+        Ok((columns, data))
+    }
+
+    /// Column metadata from `PRAGMA table_info`, in table order. Used instead of the result
+    /// set so headers and types are available even when a page has no rows.
+    fn table_column_details(&self, table_name: &str) -> Result<Vec<ColumnDetail>, String> {
+        let query = format!("PRAGMA table_info({})", Self::quote_identifier(table_name));
+        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                let data_type: String = row.get(2)?;
+                let is_boolean = matches!(
+                    data_type.to_lowercase().as_str(),
+                    "tinyint(1)" | "boolean" | "bool"
+                );
+                Ok(ColumnDetail {
+                    name: row.get(1)?,
+                    data_type: if data_type.is_empty() {
+                        "UNKNOWN".to_string()
+                    } else {
+                        data_type
+                    },
+                    is_nullable: row.get::<_, i64>(3)? == 0,
+                    default_value: row.get(4)?,
+                    is_boolean,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Primary key columns of a table, ordered by their position in the key.
+    fn primary_key_columns(&self, table_name: &str) -> Result<Vec<String>, String> {
+        let query = format!("PRAGMA table_info({})", Self::quote_identifier(table_name));
+        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(5)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut pk_columns = Vec::new();
+        for row in rows {
+            let (position, name) = row.map_err(|e| e.to_string())?;
+            if position > 0 {
+                pk_columns.push((position, name));
+            }
+        }
+        pk_columns.sort_by_key(|(position, _)| *position);
+        Ok(pk_columns.into_iter().map(|(_, name)| name).collect())
+    }
 }
 
 impl DbBackend for SqliteBackend {
+    fn canceller(&self) -> Option<Arc<dyn QueryCanceller>> {
+        Some(Arc::new(SqliteCanceller {
+            handle: self.conn.get_interrupt_handle(),
+            running: Arc::clone(&self.running),
+        }))
+    }
+
+    fn get_databases(&mut self, _include_system: bool) -> Result<Vec<String>, String> {
+        // A SQLite connection maps to a single file, so that file is the only "database"
+        let name = Path::new(&self.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.clone());
+        Ok(vec![name])
+    }
+
     fn get_tables(&mut self) -> Result<Vec<String>, String> {
         let mut stmt = self
             .conn
@@ -381,11 +1760,136 @@ impl DbBackend for SqliteBackend {
             .query_map([], |row| row.get(0))
             .map_err(|e| e.to_string())?;
 
-        let mut tables = Vec::new();
+        let mut tables = Vec::new();
+        for row in rows {
+            tables.push(row.map_err(|e| e.to_string())?);
+        }
+        Ok(tables)
+    }
+
+    fn get_foreign_keys(&mut self, table_name: &str) -> Result<Vec<ForeignKey>, String> {
+        let query = format!(
+            "PRAGMA foreign_key_list({})",
+            Self::quote_identifier(table_name)
+        );
+        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        // Columns: id, seq, table, from, to, on_update, on_delete, match
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| e.to_string())?);
+        }
+        entries.sort_by_key(|(id, seq, ..)| (*id, *seq));
+
+        let mut foreign_keys = Vec::new();
+        for (id, seq, referenced_table, column, referenced_column) in entries {
+            // SQLite leaves `to` empty when the FK points at the referenced table's primary key
+            let referenced_column = match referenced_column {
+                Some(col) => col,
+                None => self
+                    .primary_key_columns(&referenced_table)?
+                    .get(seq as usize)
+                    .cloned()
+                    .unwrap_or_else(|| "rowid".to_string()),
+            };
+            foreign_keys.push(ForeignKey {
+                // SQLite doesn't name FK constraints, so group entries by their list id
+                constraint_name: format!("fk_{}_{}", table_name, id),
+                column,
+                referenced_table,
+                referenced_column,
+            });
+        }
+        Ok(foreign_keys)
+    }
+
+    fn get_indexes(&mut self, table_name: &str) -> Result<Vec<IndexInfo>, String> {
+        let query = format!("PRAGMA index_list({})", Self::quote_identifier(table_name));
+        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        // Columns: seq, name, unique, origin ("c", "u" or "pk"), partial
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut index_list = Vec::new();
         for row in rows {
-            tables.push(row.map_err(|e| e.to_string())?);
+            index_list.push(row.map_err(|e| e.to_string())?);
         }
-        Ok(tables)
+
+        let mut indexes = Vec::new();
+        for (name, is_unique, origin) in index_list {
+            let query = format!("PRAGMA index_info({})", Self::quote_identifier(&name));
+            let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+            // Columns: seqno, cid, name (NULL for expression indexes)
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(2)?))
+                })
+                .map_err(|e| e.to_string())?;
+
+            let mut columns = Vec::new();
+            for row in rows {
+                columns.push(row.map_err(|e| e.to_string())?);
+            }
+            columns.sort_by_key(|(seqno, _)| *seqno);
+
+            indexes.push(IndexInfo {
+                name,
+                columns: columns.into_iter().filter_map(|(_, col)| col).collect(),
+                is_unique,
+                is_primary: origin == "pk",
+            });
+        }
+
+        // An INTEGER PRIMARY KEY aliases the rowid and has no backing index, so report it explicitly
+        if !indexes.iter().any(|i| i.is_primary) {
+            let pk_columns = self.primary_key_columns(table_name)?;
+            if !pk_columns.is_empty() {
+                indexes.insert(
+                    0,
+                    IndexInfo {
+                        name: "PRIMARY".to_string(),
+                        columns: pk_columns,
+                        is_unique: true,
+                        is_primary: true,
+                    },
+                );
+            }
+        }
+        Ok(indexes)
+    }
+
+    fn get_primary_key(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        self.primary_key_columns(table_name)
+    }
+
+    fn get_column_names(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        Ok(self
+            .table_column_details(table_name)?
+            .into_iter()
+            .map(|column| column.name)
+            .collect())
+    }
+
+    fn get_text_columns(&mut self, table_name: &str) -> Result<Vec<String>, String> {
+        self.get_column_names(table_name)
     }
 
     fn get_table_data(
@@ -393,145 +1897,155 @@ impl DbBackend for SqliteBackend {
         table_name: &str,
         page: u32,
         per_page: u32,
-        where_clause: Option<String>,
+        filter: &RowFilter,
         sort_column: Option<String>,
         sort_direction: Option<String>,
     ) -> Result<TableData, String> {
+        let _running = InFlight::start(&self.running, true);
         let start = std::time::Instant::now();
         // Logic similar to MySql implementation but for SQLite
-        let mut where_clause_for_select = String::new();
         let limit = per_page;
-        let mut has_limit_in_where = false;
-
-        if let Some(clause) = where_clause {
-            if !clause.trim().is_empty() {
-                let upper_clause = clause.to_uppercase();
-                if upper_clause.rfind("LIMIT").is_some() {
-                    where_clause_for_select = format!(" WHERE {}", clause);
-                    has_limit_in_where = true;
-                } else {
-                    where_clause_for_select = format!(" WHERE {}", clause);
-                    // where_clause_for_count = where_clause_for_select.clone(); // Unused
-                }
-            }
-        }
-
-        let offset = (page - 1) * limit;
+        let (where_clause_for_select, params) = build_where_clause(filter, Self::quote_identifier)?;
+        let offset = page_offset(page, limit)?;
 
         // Sorting
-        let mut order_by_clause = String::new();
-        if let Some(col) = sort_column {
-            if !col.trim().is_empty() {
-                let dir = sort_direction
-                    .map(|d| d.to_uppercase())
-                    .unwrap_or_else(|| "ASC".to_string());
-                let dir = if dir == "DESC" { "DESC" } else { "ASC" };
-
-                // Sanitize/Quote for SQLite (double quotes)
-                order_by_clause = format!(" ORDER BY \"{}\" {}", col.replace("\"", "\"\""), dir);
-            }
-        }
+        let order_by_clause = match sort_column.as_deref() {
+            Some(col) if !col.trim().is_empty() => build_order_by_clause(
+                &self.get_column_names(table_name)?,
+                col,
+                sort_direction.as_deref(),
+                Self::quote_identifier,
+            )?,
+            _ => String::new(),
+        };
 
         // Data
         // We fetch one more row than requested to determine has_more
-        let query = if has_limit_in_where {
-            format!("SELECT * FROM {}{}", table_name, where_clause_for_select)
-        } else {
-            format!(
-                "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
-                table_name,
-                where_clause_for_select,
-                order_by_clause,
-                limit + 1,
-                offset
-            )
-        };
+        let query = format!(
+            "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
+            Self::quote_identifier(table_name),
+            where_clause_for_select,
+            order_by_clause,
+            limit as u64 + 1,
+            offset
+        );
 
-        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        let (columns, mut data) =
+            self.collect_rows(&query, rusqlite::params_from_iter(params.iter()))?;
+        let column_details = self.table_column_details(table_name)?;
 
-        // We need column names. To avoid borrow checker issues with stmt,
-        // we can collect them first, drop the borrow, then query.
-        let columns: Vec<String> = stmt
-            .column_names()
-            .into_iter()
-            .map(|s| s.to_string())
-            .collect();
+        let mut has_more = false;
+        if data.len() > limit as usize {
+            data.pop();
+            has_more = true;
+        }
 
-        let column_count = columns.len();
+        Ok(TableData {
+            // No COUNT(*) here: on a big table it can take longer than the page itself, so
+            // the UI shows the page first and asks `count_rows` for the total separately
+            total: 0,
+            has_more,
+            columns,
+            column_details,
+            rows: data,
+            execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor: None,
+            affected_rows: None,
+        })
+    }
 
-        let rows = stmt
-            .query_map([], |row| {
-                let mut map = HashMap::new();
-                for i in 0..column_count {
-                    let val: SqliteValue = row.get(i)?;
-                    let name = &columns[i];
-                    map.insert(name.clone(), Self::convert_value(val));
-                }
-                Ok(map)
-            })
-            .map_err(|e| e.to_string())?;
+    fn get_table_data_keyset(
+        &mut self,
+        table_name: &str,
+        pk_column: &str,
+        after_pk: Option<String>,
+        per_page: u32,
+    ) -> Result<TableData, String> {
+        let _running = InFlight::start(&self.running, true);
+        let start = std::time::Instant::now();
 
-        let mut data = Vec::new();
-        let mut column_details = Vec::new();
-        for row in rows {
-            data.push(row.map_err(|e| e.to_string())?);
-        }
+        let table = Self::quote_identifier(table_name);
+        let pk = Self::quote_identifier(pk_column);
+        let limit = per_page as u64 + 1; // One extra row to determine has_more
+
+        let (columns, mut data) = match after_pk {
+            Some(after) => self.collect_rows(
+                &format!("SELECT * FROM {table} WHERE {pk} > ?1 ORDER BY {pk} LIMIT {limit}"),
+                rusqlite::params![after],
+            )?,
+            None => self.collect_rows(
+                &format!("SELECT * FROM {table} ORDER BY {pk} LIMIT {limit}"),
+                [],
+            )?,
+        };
 
-        // Populate column details - minimal info for SQLite since getting metadata is harder here
-        // We could run PRAGMA table_info(table_name) separately if needed, but for now basic info
-        for col_name in &columns {
-            column_details.push(ColumnDetail {
-                name: col_name.clone(),
-                data_type: "UNKNOWN".to_string(),
-                is_nullable: true,
-                default_value: None,
-            });
-        }
+        let column_details = self.table_column_details(table_name)?;
 
         let mut has_more = false;
-        if data.len() > limit as usize {
+        if data.len() > per_page as usize {
             data.pop();
             has_more = true;
         }
+        let next_cursor = if has_more {
+            data.last()
+                .and_then(|row| row.get(pk_column).cloned().flatten())
+        } else {
+            None
+        };
 
         Ok(TableData {
-            total: 0, // Deprecated/Unused
+            total: 0,
             has_more,
             columns,
             column_details,
             rows: data,
             execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor,
+            affected_rows: None,
         })
     }
 
-    fn execute_query(&mut self, query: &str) -> Result<TableData, String> {
+    fn execute_query(&mut self, query: &str, use_transaction: bool) -> Result<TableData, String> {
+        let _running = InFlight::start(&self.running, true);
         let start = std::time::Instant::now();
-        let mut stmt = self.conn.prepare(query).map_err(|e| e.to_string())?;
-
-        let columns: Vec<String> = stmt
-            .column_names()
-            .into_iter()
-            .map(|s| s.to_string())
-            .collect();
+        let statements = split_sql_statements(query);
+        if statements.is_empty() {
+            return Err("Query is empty".to_string());
+        }
 
-        let column_count = columns.len();
+        if use_transaction {
+            self.conn
+                .execute_batch("BEGIN")
+                .map_err(|e| e.to_string())?;
+        }
 
-        let rows = stmt
-            .query_map([], |row| {
-                let mut map = HashMap::new();
-                for i in 0..column_count {
-                    let val: SqliteValue = row.get(i)?;
-                    let name = &columns[i];
-                    map.insert(name.clone(), Self::convert_value(val));
+        let mut result = (Vec::new(), Vec::new());
+        for (index, statement) in statements.iter().enumerate() {
+            match self.collect_rows(statement, []) {
+                Ok(rows) => result = rows,
+                Err(e) => {
+                    if use_transaction {
+                        let _ = self.conn.execute_batch("ROLLBACK");
+                    }
+                    return Err(format!("Statement {} failed: {}", index + 1, e));
                 }
-                Ok(map)
-            })
-            .map_err(|e| e.to_string())?;
+            }
+        }
 
-        let mut data = Vec::new();
-        for row in rows {
-            data.push(row.map_err(|e| e.to_string())?);
+        if use_transaction {
+            self.conn
+                .execute_batch("COMMIT")
+                .map_err(|e| e.to_string())?;
         }
+        let (columns, data) = result;
+        let affected_rows = match statements.last() {
+            // changes() keeps the count of the last DML statement, so DDL reports 0 instead
+            Some(last) if !returns_rows(last) => match first_keyword(last).as_str() {
+                "INSERT" | "UPDATE" | "DELETE" | "REPLACE" => Some(self.conn.changes()),
+                _ => Some(0),
+            },
+            _ => None,
+        };
 
         let mut column_details = Vec::new();
         for col_name in &columns {
@@ -540,32 +2054,32 @@ impl DbBackend for SqliteBackend {
                 data_type: "UNKNOWN".to_string(),
                 is_nullable: true,
                 default_value: None,
+                is_boolean: false,
             });
         }
 
         Ok(TableData {
-            total: data.len() as u32,
+            total: data.len() as u64,
             has_more: false,
             columns,
             column_details,
             rows: data,
             execution_duration_ms: Some(start.elapsed().as_millis() as u64),
+            next_cursor: None,
+            affected_rows,
         })
     }
 
-    fn delete_row(
-        &mut self,
-        table_name: &str,
-        pk_column: &str,
-        pk_value: &str,
-    ) -> Result<u64, String> {
+    fn delete_row(&mut self, table_name: &str, pk: &[(String, String)]) -> Result<u64, String> {
+        let (predicate, pk_values) = build_pk_predicate(pk, Self::quote_identifier)?;
         let stmt = format!(
-            "DELETE FROM \"{}\" WHERE \"{}\" = ?1",
-            table_name, pk_column
+            "DELETE FROM {} WHERE {}",
+            Self::quote_identifier(table_name),
+            predicate
         );
         let affected = self
             .conn
-            .execute(&stmt, rusqlite::params![pk_value])
+            .execute(&stmt, rusqlite::params_from_iter(pk_values.iter()))
             .map_err(|e| e.to_string())?;
         Ok(affected as u64)
     }
@@ -573,26 +2087,35 @@ impl DbBackend for SqliteBackend {
     fn update_row(
         &mut self,
         table_name: &str,
-        pk_column: &str,
-        pk_value: &str,
+        pk: &[(String, String)],
+        expected: Option<&HashMap<String, Option<String>>>,
         data: HashMap<String, Option<String>>,
     ) -> Result<u64, String> {
+        let (mut predicate, mut where_values) = build_pk_predicate(pk, Self::quote_identifier)?;
+        if let Some(expected) = expected {
+            let (conditions, values) = build_expected_predicate(expected, Self::quote_identifier)?;
+            predicate.push_str(&conditions);
+            where_values.extend(values);
+        }
         let mut sets = Vec::new();
 
         let mut param_values: Vec<Option<String>> = Vec::new();
         for (key, value) in &data {
-            if key != pk_column {
-                sets.push(format!("\"{}\" = ?", key));
+            if !pk.iter().any(|(pk_column, _)| pk_column == key) {
+                sets.push(format!("{} = ?", Self::quote_identifier(key)));
                 param_values.push(value.clone());
             }
         }
-        param_values.push(Some(pk_value.to_string()));
+        if sets.is_empty() {
+            return Err("No columns to update".to_string());
+        }
+        param_values.extend(where_values.into_iter().map(Some));
 
         let query = format!(
-            "UPDATE \"{}\" SET {} WHERE \"{}\" = ?",
-            table_name,
+            "UPDATE {} SET {} WHERE {}",
+            Self::quote_identifier(table_name),
             sets.join(", "),
-            pk_column
+            predicate
         );
 
         // rusqlite's params_from_iter expects something that iterates into ToSql
@@ -600,50 +2123,631 @@ impl DbBackend for SqliteBackend {
             .conn
             .execute(&query, rusqlite::params_from_iter(param_values.iter()))
             .map_err(|e| e.to_string())?;
+        if expected.is_some() && affected == 0 {
+            return Err(ROW_MODIFIED_ERROR.to_string());
+        }
         Ok(affected as u64)
     }
 
-    fn get_total_rows(
+    fn count_rows(&mut self, table_name: &str, filter: &RowFilter) -> Result<u64, String> {
+        let _running = InFlight::start(&self.running, true);
+        let (query, params) = build_count_query(table_name, filter, Self::quote_identifier)?;
+        let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
+        let count: u64 = stmt
+            .query_row(rusqlite::params_from_iter(params.iter()), |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+
+        Ok(count)
+    }
+
+    fn create_table(
         &mut self,
         table_name: &str,
-        where_clause: Option<String>,
-    ) -> Result<u64, String> {
-        let mut where_clause_for_count = String::new();
+        columns: &[ColumnDefinition],
+    ) -> Result<(), String> {
+        if self.get_tables()?.iter().any(|t| t == table_name) {
+            return Err(format!("Table '{}' already exists", table_name));
+        }
+        let query = render_create_table(
+            table_name,
+            columns,
+            Self::quote_identifier,
+            Self::quote_literal,
+        )?;
+        self.conn.execute_batch(&query).map_err(|e| e.to_string())
+    }
 
-        if let Some(clause) = where_clause {
-            if !clause.trim().is_empty() {
-                where_clause_for_count = format!(" WHERE {}", clause);
-            }
+    fn drop_table(&mut self, table_name: &str) -> Result<(), String> {
+        validate_identifier(table_name)?;
+        let query = format!("DROP TABLE {}", Self::quote_identifier(table_name));
+        self.conn.execute_batch(&query).map_err(|e| e.to_string())
+    }
+
+    fn truncate_table(&mut self, table_name: &str) -> Result<u64, String> {
+        validate_identifier(table_name)?;
+        // SQLite has no TRUNCATE; delete the rows and reset the AUTOINCREMENT counter if any.
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        let affected = tx
+            .execute(
+                &format!("DELETE FROM {}", Self::quote_identifier(table_name)),
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+        let has_sequence: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if has_sequence {
+            tx.execute(
+                "DELETE FROM sqlite_sequence WHERE name = ?1",
+                rusqlite::params![table_name],
+            )
+            .map_err(|e| e.to_string())?;
         }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(affected as u64)
+    }
+
+    fn column_stats(&mut self, table_name: &str, column: &str) -> Result<ColumnStats, String> {
+        let detail = self
+            .table_column_details(table_name)?
+            .into_iter()
+            .find(|c| c.name == column)
+            .ok_or_else(|| format!("Unknown column '{}' in table '{}'", column, table_name))?;
+        // Follows SQLite's type affinity rules, except that DATE/DATETIME (NUMERIC affinity
+        // but usually stored as text) aren't averaged
+        let declared = detail.data_type.to_uppercase();
+        let kind = if ["INT", "REAL", "FLOA", "DOUB", "DEC", "NUMERIC", "BOOL"]
+            .iter()
+            .any(|t| declared.contains(t))
+        {
+            StatsKind::Numeric
+        } else if declared.contains("BLOB") {
+            StatsKind::Binary
+        } else {
+            StatsKind::Comparable
+        };
+
+        let query = build_column_stats_query(table_name, column, kind, Self::quote_identifier);
+        let _running = InFlight::start(&self.running, true);
+        self.conn
+            .query_row(&query, [], |row| {
+                let rows = row.get::<_, i64>(0)? as u64;
+                Ok(ColumnStats {
+                    column: column.to_string(),
+                    data_type: detail.data_type.clone(),
+                    rows,
+                    null_count: row.get::<_, i64>(1)? as u64,
+                    distinct_count: row.get::<_, i64>(5)? as u64,
+                    distinct_approximate: rows > DISTINCT_SAMPLE_ROWS,
+                    min: Self::convert_value(row.get(2)?),
+                    max: Self::convert_value(row.get(3)?),
+                    average: row.get(4)?,
+                })
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<RowCount, String> {
+        Ok(RowCount {
+            count: self.count_rows(table_name, &RowFilter::Columns(Vec::new()))?,
+            estimated: false,
+        })
+    }
 
+    fn row_as_insert(
+        &mut self,
+        table_name: &str,
+        pk: &[(String, String)],
+    ) -> Result<Option<String>, String> {
+        let (predicate, pk_values) = build_pk_predicate(pk, Self::quote_identifier)?;
         let query = format!(
-            "SELECT COUNT(*) FROM {}{}",
-            table_name, where_clause_for_count
+            "SELECT * FROM {} WHERE {} LIMIT 1",
+            Self::quote_identifier(table_name),
+            predicate
         );
-
         let mut stmt = self.conn.prepare(&query).map_err(|e| e.to_string())?;
-        let count: u64 = stmt
-            .query_row([], |row| row.get(0))
+        let columns: Vec<String> = stmt
+            .column_names()
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut rows = stmt
+            .query(rusqlite::params_from_iter(pk_values.iter()))
             .map_err(|e| e.to_string())?;
+        let row = match rows.next().map_err(|e| e.to_string())? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
 
-        Ok(count)
+        let mut values = Vec::with_capacity(columns.len());
+        for i in 0..columns.len() {
+            let value: SqliteValue = row.get(i).map_err(|e| e.to_string())?;
+            values.push(Self::sql_literal(value));
+        }
+        Ok(Some(render_insert(
+            table_name,
+            &columns,
+            &[values],
+            Self::quote_identifier,
+        )))
+    }
+
+    fn dump_tables(
+        &mut self,
+        tables: &[String],
+        out: &mut dyn Write,
+        on_table: &mut dyn FnMut(&str, u64),
+    ) -> Result<u64, String> {
+        let _running = InFlight::start(&self.running, true);
+        writeln!(out, "PRAGMA foreign_keys = OFF;\nBEGIN TRANSACTION;\n")
+            .map_err(|e| e.to_string())?;
+
+        let mut total = 0;
+        for table in tables {
+            let quoted = Self::quote_identifier(table);
+            let ddl: String = self
+                .conn
+                .query_row(
+                    "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                    [table],
+                    |row| row.get(0),
+                )
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => {
+                        format!("Table '{}' not found", table)
+                    }
+                    e => e.to_string(),
+                })?;
+            writeln!(out, "DROP TABLE IF EXISTS {};\n{};\n", quoted, ddl)
+                .map_err(|e| e.to_string())?;
+
+            let mut stmt = self
+                .conn
+                .prepare(&format!("SELECT * FROM {}", quoted))
+                .map_err(|e| e.to_string())?;
+            let columns: Vec<String> = stmt
+                .column_names()
+                .into_iter()
+                .map(|s| s.to_string())
+                .collect();
+            let rows = stmt
+                .query_map([], |row| {
+                    let mut values = Vec::with_capacity(columns.len());
+                    for i in 0..columns.len() {
+                        values.push(Self::sql_literal(row.get(i)?));
+                    }
+                    Ok(values)
+                })
+                .map_err(|e| e.to_string())?
+                .map(|row| row.map_err(|e| e.to_string()));
+            let count = write_insert_batches(out, table, &columns, rows, Self::quote_identifier)?;
+
+            // Indexes and triggers come after the data so they are built once
+            let mut stmt = self
+                .conn
+                .prepare(
+                    "SELECT sql FROM sqlite_master
+                     WHERE tbl_name = ?1 AND type IN ('index', 'trigger') AND sql IS NOT NULL",
+                )
+                .map_err(|e| e.to_string())?;
+            let definitions = stmt
+                .query_map([table], |row| row.get::<_, String>(0))
+                .map_err(|e| e.to_string())?;
+            for definition in definitions {
+                writeln!(out, "{};", definition.map_err(|e| e.to_string())?)
+                    .map_err(|e| e.to_string())?;
+            }
+            writeln!(out).map_err(|e| e.to_string())?;
+            on_table(table, count);
+            total += count;
+        }
+
+        // Restore AUTOINCREMENT counters, which can be ahead of the largest id
+        let has_sequence: bool = self
+            .conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if has_sequence {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT name, seq FROM sqlite_sequence")
+                .map_err(|e| e.to_string())?;
+            let sequences = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })
+                .map_err(|e| e.to_string())?;
+            for sequence in sequences {
+                let (name, seq) = sequence.map_err(|e| e.to_string())?;
+                if tables.contains(&name) {
+                    let name = Self::quote_literal(&name);
+                    writeln!(
+                        out,
+                        "DELETE FROM sqlite_sequence WHERE name = {};\nINSERT INTO sqlite_sequence (name, seq) VALUES ({}, {});",
+                        name, name, seq
+                    )
+                    .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        writeln!(out, "COMMIT;").map_err(|e| e.to_string())?;
+        Ok(total)
+    }
+
+    fn run_script(
+        &mut self,
+        statements: &[String],
+        use_transaction: bool,
+    ) -> Result<SqlScriptResult, String> {
+        let _running = InFlight::start(&self.running, true);
+        if use_transaction {
+            self.conn
+                .execute_batch("BEGIN")
+                .map_err(|e| e.to_string())?;
+        }
+
+        for (index, statement) in statements.iter().enumerate() {
+            if let Err(e) = self.run_statement(statement) {
+                if use_transaction {
+                    let _ = self.conn.execute_batch("ROLLBACK");
+                }
+                return Ok(SqlScriptResult {
+                    total: statements.len(),
+                    executed: index,
+                    failed: Some(FailedStatement {
+                        index: index + 1,
+                        statement: statement.clone(),
+                        error: e,
+                    }),
+                    rolled_back: use_transaction,
+                });
+            }
+        }
+
+        if use_transaction {
+            self.conn
+                .execute_batch("COMMIT")
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(SqlScriptResult {
+            total: statements.len(),
+            executed: statements.len(),
+            failed: None,
+            rolled_back: false,
+        })
     }
 }
 
-pub fn get_db_backend(
-    creds: &DbCredentials,
-    project_path: &str,
-) -> Result<Box<dyn DbBackend + Send>, String> {
+#[tracing::instrument(skip_all, err)]
+pub fn get_db_backend(creds: &DbCredentials, project_path: &str) -> CommandResult<Backend> {
+    let from_url;
+    let creds = match creds.url.as_deref().filter(|url| !url.trim().is_empty()) {
+        Some(url) => {
+            from_url = DbCredentials::from_url(url).map_err(CommandError::InvalidInput)?;
+            &from_url
+        }
+        None => creds,
+    };
+
+    tracing::info!(
+        connection = %creds.connection,
+        host = creds.host.as_deref().unwrap_or(""),
+        database = %creds.database,
+        "Connecting to database"
+    );
     match creds.connection.as_str() {
-        "mysql" => Ok(Box::new(MySqlBackend::new(creds)?)),
+        "mysql" => Ok(Backend::Sql(Box::new(
+            MySqlBackend::new(creds).map_err(CommandError::DbConnection)?,
+        ))),
         "sqlite" => {
             let path = Path::new(project_path).join(&creds.database);
-            let path_str = path.to_str().ok_or("Invalid database path")?;
-            Ok(Box::new(SqliteBackend::new(path_str)?))
+            let path_str = path
+                .to_str()
+                .ok_or_else(|| CommandError::InvalidInput("Invalid database path".to_string()))?;
+            Ok(Backend::Sql(Box::new(
+                SqliteBackend::new(path_str, creds).map_err(CommandError::DbConnection)?,
+            )))
         }
-        _ => Err(format!(
+        "redis" => Ok(Backend::KeyValue(Box::new(
+            RedisBackend::new(creds).map_err(CommandError::DbConnection)?,
+        ))),
+        _ => Err(CommandError::Unsupported(format!(
             "Unsupported database connection type: {}",
             creds.connection
-        )),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::db_types::ColumnFilter;
+
+    fn column_filter(column: &str, op: FilterOperator, value: Option<&str>) -> ColumnFilter {
+        ColumnFilter {
+            column: column.to_string(),
+            op,
+            value: value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn count_query_without_filter() {
+        let (query, params) = build_count_query(
+            "users",
+            &RowFilter::Columns(vec![]),
+            MySqlBackend::quote_identifier,
+        )
+        .unwrap();
+        assert_eq!(query, "SELECT COUNT(*) FROM `users`");
+        assert!(params.is_empty());
+
+        let (query, _) = build_count_query(
+            "users",
+            &RowFilter::Raw("  ".to_string()),
+            SqliteBackend::quote_identifier,
+        )
+        .unwrap();
+        assert_eq!(query, "SELECT COUNT(*) FROM \"users\"");
+    }
+
+    #[test]
+    fn count_query_with_structured_filter() {
+        let filter = RowFilter::Columns(vec![
+            column_filter("age", FilterOperator::Gt, Some("18")),
+            column_filter("deleted_at", FilterOperator::IsNull, None),
+        ]);
+        let (query, params) =
+            build_count_query("users", &filter, MySqlBackend::quote_identifier).unwrap();
+        assert_eq!(
+            query,
+            "SELECT COUNT(*) FROM `users` WHERE `age` > ? AND `deleted_at` IS NULL"
+        );
+        assert_eq!(params, ["18"]);
+
+        let missing_value =
+            RowFilter::Columns(vec![column_filter("age", FilterOperator::Eq, None)]);
+        assert!(
+            build_count_query("users", &missing_value, MySqlBackend::quote_identifier).is_err()
+        );
+    }
+
+    #[test]
+    fn count_query_with_search() {
+        let filter = RowFilter::Search {
+            columns: vec!["name".to_string(), "email".to_string()],
+            query: "50%_off".to_string(),
+        };
+        let (query, params) =
+            build_count_query("users", &filter, SqliteBackend::quote_identifier).unwrap();
+        assert_eq!(
+            query,
+            "SELECT COUNT(*) FROM \"users\" WHERE (\"name\" LIKE ? ESCAPE '!' OR \"email\" LIKE ? ESCAPE '!')"
+        );
+        assert_eq!(params, ["%50!%!_off%", "%50!%!_off%"]);
+
+        let nothing = RowFilter::Search {
+            columns: vec![],
+            query: "x".to_string(),
+        };
+        let (query, _) =
+            build_count_query("users", &nothing, SqliteBackend::quote_identifier).unwrap();
+        assert_eq!(query, "SELECT COUNT(*) FROM \"users\" WHERE 1 = 0");
+    }
+
+    #[test]
+    fn count_query_with_raw_filter() {
+        let filter = RowFilter::Raw("id IN (1, 2)".to_string());
+        let (query, params) =
+            build_count_query("users", &filter, MySqlBackend::quote_identifier).unwrap();
+        assert_eq!(query, "SELECT COUNT(*) FROM `users` WHERE id IN (1, 2)");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn sqlite_count_rows() {
+        let mut backend = SqliteBackend::new(":memory:", &DbCredentials::default()).unwrap();
+        backend
+            .conn
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, deleted_at TEXT);
+                 INSERT INTO users (name, deleted_at) VALUES
+                     ('Ada', NULL), ('Grace', '2024-01-01'), ('Linus', NULL);",
+            )
+            .unwrap();
+
+        assert_eq!(
+            backend
+                .count_rows("users", &RowFilter::Columns(vec![]))
+                .unwrap(),
+            3
+        );
+        let active = RowFilter::Columns(vec![column_filter(
+            "deleted_at",
+            FilterOperator::IsNull,
+            None,
+        )]);
+        assert_eq!(backend.count_rows("users", &active).unwrap(), 2);
+        let search = RowFilter::Search {
+            columns: vec!["name".to_string()],
+            query: "a".to_string(),
+        };
+        assert_eq!(backend.count_rows("users", &search).unwrap(), 2);
+        assert!(backend
+            .count_rows("missing", &RowFilter::Columns(vec![]))
+            .is_err());
+    }
+
+    #[test]
+    fn page_offset_does_not_overflow() {
+        assert!(page_offset(0, 50).is_err());
+        assert_eq!(page_offset(1, 50).unwrap(), 0);
+        assert_eq!(page_offset(3, 50).unwrap(), 100);
+        // (u32::MAX - 1) * 1000 wraps in u32 arithmetic
+        assert_eq!(
+            page_offset(u32::MAX, 1000).unwrap(),
+            (u32::MAX as u64 - 1) * 1000
+        );
+        assert_eq!(
+            page_offset(u32::MAX, u32::MAX).unwrap(),
+            (u32::MAX as u64 - 1) * u32::MAX as u64
+        );
+    }
+
+    #[test]
+    fn sqlite_page_past_the_end_is_empty() {
+        let mut backend = SqliteBackend::new(":memory:", &DbCredentials::default()).unwrap();
+        backend
+            .conn
+            .execute_batch(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES;",
+            )
+            .unwrap();
+
+        let data = backend
+            .get_table_data(
+                "items",
+                u32::MAX,
+                1000,
+                &RowFilter::Columns(vec![]),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(data.rows.is_empty());
+        assert!(!data.has_more);
+    }
+
+    #[test]
+    fn mysql_time_folds_days_into_hours() {
+        let column = mysql::Column::new(ColumnType::MYSQL_TYPE_TIME);
+        let time = MySqlValue::Time(false, 5, 12, 30, 0, 0);
+        assert_eq!(
+            MySqlBackend::convert_value(&time, &column).as_deref(),
+            Some("132:30:00")
+        );
+        let time = MySqlValue::Time(false, 0, 7, 5, 9, 0);
+        assert_eq!(
+            MySqlBackend::convert_value(&time, &column).as_deref(),
+            Some("07:05:09")
+        );
+    }
+
+    #[test]
+    fn mysql_negative_time_keeps_its_sign() {
+        let column = mysql::Column::new(ColumnType::MYSQL_TYPE_TIME);
+        let time = MySqlValue::Time(true, 1, 2, 3, 4, 0);
+        assert_eq!(
+            MySqlBackend::convert_value(&time, &column).as_deref(),
+            Some("-26:03:04")
+        );
+
+        // TIME(3) pads the fraction to the declared precision
+        let column = mysql::Column::new(ColumnType::MYSQL_TYPE_TIME).with_decimals(3);
+        let time = MySqlValue::Time(true, 34, 22, 59, 59, 120_000);
+        assert_eq!(
+            MySqlBackend::convert_value(&time, &column).as_deref(),
+            Some("-838:59:59.120")
+        );
+    }
+
+    #[test]
+    fn mysql_decimal_round_trips_unchanged() {
+        let column = mysql::Column::new(ColumnType::MYSQL_TYPE_NEWDECIMAL).with_decimals(4);
+        for text in ["12345678901234567890.1234", "-0.0100", "0.0000"] {
+            let value = MySqlValue::Bytes(text.as_bytes().to_vec());
+            assert_eq!(
+                MySqlBackend::convert_value(&value, &column).as_deref(),
+                Some(text)
+            );
+        }
+
+        // Binary FLOAT/DOUBLE values are padded to the declared scale without f64 noise
+        let column = mysql::Column::new(ColumnType::MYSQL_TYPE_FLOAT).with_decimals(2);
+        assert_eq!(
+            MySqlBackend::convert_value(&MySqlValue::Float(0.1), &column).as_deref(),
+            Some("0.10")
+        );
+    }
+
+    fn pivot_key(user_id: &str, role_id: &str) -> Vec<(String, String)> {
+        vec![
+            ("user_id".to_string(), user_id.to_string()),
+            ("role_id".to_string(), role_id.to_string()),
+        ]
+    }
+
+    #[test]
+    fn pk_predicate_for_a_two_column_key() {
+        let (predicate, values) =
+            build_pk_predicate(&pivot_key("7", "2"), MySqlBackend::quote_identifier).unwrap();
+        assert_eq!(predicate, "`user_id` = ? AND `role_id` = ?");
+        assert_eq!(values, ["7", "2"]);
+
+        let (predicate, _) =
+            build_pk_predicate(&pivot_key("7", "2"), SqliteBackend::quote_identifier).unwrap();
+        assert_eq!(predicate, "\"user_id\" = ? AND \"role_id\" = ?");
+
+        assert!(build_pk_predicate(&[], MySqlBackend::quote_identifier).is_err());
+    }
+
+    #[test]
+    fn sqlite_edits_one_row_of_a_pivot_table() {
+        let mut backend = SqliteBackend::new(":memory:", &DbCredentials::default()).unwrap();
+        backend
+            .conn
+            .execute_batch(
+                "CREATE TABLE role_user (
+                     user_id INTEGER, role_id INTEGER, note TEXT,
+                     PRIMARY KEY (user_id, role_id)
+                 );
+                 INSERT INTO role_user VALUES (1, 1, 'a'), (1, 2, 'b'), (2, 1, 'c');",
+            )
+            .unwrap();
+
+        let data = HashMap::from([("note".to_string(), Some("changed".to_string()))]);
+        assert_eq!(
+            backend
+                .update_row("role_user", &pivot_key("1", "2"), None, data)
+                .unwrap(),
+            1
+        );
+        let note: String = backend
+            .conn
+            .query_row(
+                "SELECT note FROM role_user WHERE user_id = 1 AND role_id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(note, "changed");
+
+        assert_eq!(
+            backend
+                .delete_row("role_user", &pivot_key("1", "1"))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            backend
+                .delete_row("role_user", &pivot_key("1", "1"))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            backend
+                .count_rows("role_user", &RowFilter::Columns(vec![]))
+                .unwrap(),
+            2
+        );
     }
 }
@@ -0,0 +1,96 @@
+use crate::database::DatabaseError;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use thiserror::Error;
+
+/// Error returned to the frontend by commands. Serialized as `{ "code": "NotFound",
+/// "message": "..." }` so the UI can branch on `code` instead of matching message text.
+#[derive(Error, Debug)]
+pub enum CommandError {
+    /// A project, file, table or configuration that doesn't exist.
+    #[error("{0}")]
+    NotFound(String),
+    /// Couldn't connect to a project's database.
+    #[error("{0}")]
+    DbConnection(String),
+    /// A query or other database operation failed.
+    #[error("{0}")]
+    Database(String),
+    #[error("{0}")]
+    Io(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::NotFound(_) => "NotFound",
+            CommandError::DbConnection(_) => "DbConnection",
+            CommandError::Database(_) => "Database",
+            CommandError::Io(_) => "Io",
+            CommandError::InvalidInput(_) => "InvalidInput",
+            CommandError::Unsupported(_) => "Unsupported",
+            CommandError::Other(_) => "Other",
+        }
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("CommandError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(e: rusqlite::Error) -> Self {
+        CommandError::Database(e.to_string())
+    }
+}
+
+impl From<mysql::Error> for CommandError {
+    fn from(e: mysql::Error) -> Self {
+        match e {
+            mysql::Error::IoError(_) | mysql::Error::UrlError(_) => {
+                CommandError::DbConnection(e.to_string())
+            }
+            _ => CommandError::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => CommandError::NotFound(e.to_string()),
+            _ => CommandError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<DatabaseError> for CommandError {
+    fn from(e: DatabaseError) -> Self {
+        CommandError::Database(e.to_string())
+    }
+}
+
+/// Helpers that still report errors as plain strings.
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Other(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::Other(message.to_string())
+    }
+}
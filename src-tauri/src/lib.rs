@@ -1,7 +1,11 @@
 mod commands;
+mod credentials;
 mod database;
 mod db_factory;
+mod error;
+mod logging;
 mod models;
+mod redis_backend;
 mod state;
 mod utils;
 
@@ -14,27 +18,39 @@ fn greet(name: &str) -> String {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     use std::sync::{mpsc, Arc, Mutex};
+    // Dropped when the app exits so buffered log lines are flushed. App::run never returns
+    // on desktop, so this can't simply live until the end of run().
+    let mut log_guard = logging::init();
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "Starting Workshop");
+
     let (tx, rx) = mpsc::channel::<String>();
     let app_state = Arc::new(state::AppState {
         project_event_tx: Mutex::new(tx),
         terminal_sessions: Mutex::new(std::collections::HashMap::new()),
+        processes: state::ProcessRegistry::default(),
+        log_watchers: Mutex::new(std::collections::HashMap::new()),
     });
 
-    let db_manager = state::DbConnectionManager {
+    let db_manager = Arc::new(state::DbConnectionManager {
         connections: Mutex::new(std::collections::HashMap::new()),
-    };
-
-    // Spawn background thread to listen for project_created events
-    let thread_state = app_state.clone();
-    std::thread::spawn(move || {
-        for project_id in rx {
-            // Directly pass Arc<AppState> reference
-            let _ =
-                crate::commands::project_commands::setup_project(project_id, thread_state.clone());
-        }
+        credentials: Mutex::new(std::collections::HashMap::new()),
     });
 
     tauri::Builder::default()
+        .setup(move |app| {
+            // Spawn background thread to listen for project_created events. It needs the
+            // app handle to report setup progress, so it starts once the app exists.
+            let app_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                for project_id in rx {
+                    let _ = crate::commands::project_commands::setup_project(
+                        project_id,
+                        app_handle.clone(),
+                    );
+                }
+            });
+            Ok(())
+        })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .manage(app_state.clone())
@@ -43,29 +59,117 @@ pub fn run() {
             greet,
             commands::project_commands::create_project,
             commands::project_commands::get_projects,
+            commands::project_commands::duplicate_project,
+            commands::project_commands::import_projects_from_dir,
+            commands::db_tool_commands::get_databases,
             commands::db_tool_commands::get_project_tables,
+            commands::db_tool_commands::get_foreign_keys,
+            commands::db_tool_commands::get_indexes,
+            commands::db_tool_commands::get_primary_key,
             commands::db_tool_commands::get_table_data,
+            commands::db_tool_commands::get_table_data_keyset,
+            commands::db_tool_commands::search_table,
             commands::db_tool_commands::get_table_total_count,
+            commands::db_tool_commands::estimate_row_count,
+            commands::db_tool_commands::column_stats,
             commands::db_tool_commands::execute_query,
+            commands::db_tool_commands::cancel_query,
+            commands::db_tool_commands::dump_database,
+            commands::db_tool_commands::run_sql_file,
+            commands::db_tool_commands::create_table,
+            commands::db_tool_commands::drop_table,
+            commands::db_tool_commands::truncate_table,
             commands::db_tool_commands::delete_row,
+            commands::db_tool_commands::delete_row_by_key,
+            commands::db_tool_commands::row_as_insert,
             commands::db_tool_commands::update_row,
+            commands::db_tool_commands::update_row_by_key,
             commands::db_tool_commands::save_db_credentials,
             commands::db_tool_commands::get_db_connection_type,
+            commands::db_tool_commands::reconnect,
+            commands::db_tool_commands::list_connections,
+            commands::db_tool_commands::redis_keys,
+            commands::db_tool_commands::redis_get,
+            commands::db_tool_commands::redis_type,
+            commands::project_commands::get_project_type,
             commands::project_commands::get_project_config,
+            commands::project_commands::set_project_config,
             commands::project_commands::update_project,
+            commands::project_commands::toggle_favorite,
             commands::project_commands::delete_project,
+            commands::project_commands::restore_project,
+            commands::project_commands::list_deleted_projects,
+            commands::project_commands::purge_project,
             commands::project_commands::open_folder,
             commands::project_commands::open_in_editor,
             commands::project_commands::get_laravel_commands,
+            commands::project_commands::refresh_env,
+            commands::laravel_commands::run_artisan,
+            commands::laravel_commands::get_migration_status,
+            commands::laravel_commands::run_migrations,
+            commands::laravel_commands::rollback_migrations,
+            commands::laravel_commands::make_migration,
+            commands::laravel_commands::run_seeder,
+            commands::laravel_commands::get_models,
+            commands::laravel_commands::clear_all_caches,
+            commands::laravel_commands::storage_link,
+            commands::laravel_commands::fix_permissions,
+            commands::composer_commands::run_composer,
+            commands::composer_commands::get_composer_dependencies,
+            commands::composer_commands::get_outdated_packages,
+            commands::npm_commands::get_npm_scripts,
+            commands::npm_commands::run_npm_script,
+            commands::npm_commands::detect_package_manager,
+            commands::quality_commands::run_tests,
+            commands::quality_commands::run_pint,
+            commands::quality_commands::run_phpstan,
+            commands::env_commands::get_env_vars,
+            commands::env_commands::set_env_var,
+            commands::env_commands::diff_env,
+            commands::env_commands::generate_app_key,
+            commands::git_commands::git_status,
+            commands::git_commands::get_git_branch,
+            commands::git_commands::git_log,
+            commands::process_commands::start_dev_server,
+            commands::process_commands::stop_dev_server,
+            commands::process_commands::get_dev_server_port,
+            commands::process_commands::list_running_processes,
+            commands::process_commands::kill_process,
             commands::log_commands::get_log_files,
             commands::log_commands::read_log_file,
+            commands::log_commands::get_log_entries,
+            commands::log_commands::clear_log_file,
+            commands::log_commands::search_logs,
+            commands::log_commands::watch_log_file,
+            commands::log_commands::stop_watching_log,
             commands::terminal_commands::spawn_pty,
             commands::terminal_commands::write_pty,
             commands::terminal_commands::resize_pty,
+            commands::terminal_commands::kill_pty,
+            commands::terminal_commands::get_pty_cwd,
+            commands::terminal_commands::signal_pty,
+            commands::terminal_commands::get_pty_scrollback,
             commands::lang_commands::get_lang_files,
             commands::lang_commands::read_lang_file,
-            commands::lang_commands::save_lang_file
+            commands::lang_commands::save_lang_file,
+            commands::lang_commands::create_locale,
+            commands::lang_commands::parse_lang_php,
+            commands::lang_commands::write_lang_php,
+            commands::lang_commands::set_translation,
+            commands::lang_commands::delete_translation,
+            commands::lang_commands::validate_placeholders,
+            commands::lang_commands::export_translations_csv,
+            commands::lang_commands::import_translations_csv
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(move |_app_handle, event| {
+            // Don't leave dev servers and workers running after the app closes
+            if let tauri::RunEvent::Exit = event {
+                for mut process in app_state.processes.drain() {
+                    commands::process_commands::kill_process_tree(&mut process.child);
+                }
+                drop(log_guard.take());
+            }
+        });
 }
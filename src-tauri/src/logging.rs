@@ -0,0 +1,59 @@
+use crate::utils::get_app_data_dir;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Environment variable holding the log filter, e.g. `debug` or `workshop_lib=trace`.
+const LOG_LEVEL_ENV: &str = "WORKSHOP_LOG";
+/// Daily log files kept in `<app data>/logs` before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Sends logs to stderr and to a daily file in the app data directory, so users can attach
+/// them to bug reports. Logs are written on a background thread that flushes when the
+/// returned guard is dropped, so keep it alive until the app exits.
+pub fn init() -> Option<WorkerGuard> {
+    let filter =
+        || EnvFilter::try_from_env(LOG_LEVEL_ENV).unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let appender = get_app_data_dir().ok().and_then(|dir| {
+        let log_dir = dir.join("logs");
+        std::fs::create_dir_all(&log_dir).ok()?;
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("workshop")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_dir)
+            .ok()
+    });
+    let (file_layer, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(filter());
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let result = tracing_subscriber::registry()
+        .with(
+            fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(filter()),
+        )
+        .with(file_layer)
+        .try_init();
+    if let Err(e) = result {
+        eprintln!("Failed to initialise logging: {}", e);
+    }
+    if guard.is_none() {
+        tracing::warn!("Could not create the log directory; logging to stderr only");
+    }
+
+    guard
+}
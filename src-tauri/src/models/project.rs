@@ -1,5 +1,9 @@
+use crate::utils::read_git_branch;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -12,6 +16,17 @@ pub struct Project {
     pub created_at: String,
     pub updated_at: String,
     pub db_config: Option<String>,
+    /// Set when the project is in the recycle bin
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Whether `location` is still a folder on disk. Computed on load, not stored.
+    #[serde(default)]
+    pub location_exists: bool,
+    /// Checked-out git branch, if the folder is a repository. Computed on load, not stored.
+    #[serde(default)]
+    pub git_branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,9 +38,76 @@ pub enum ProjectStatus {
     Abandoned,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSortField {
+    Name,
+    CreatedAt,
+    #[default]
+    UpdatedAt,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filters, sorting and paging for the project list. Favorites always come first.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct GetProjectsParams {
+    /// Matched case-insensitively against name and description
+    pub search: Option<String>,
+    pub status: Option<ProjectStatus>,
+    pub sort_by: ProjectSortField,
+    pub order: SortOrder,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectPage {
+    pub projects: Vec<Project>,
+    /// Number of projects matching the filters, ignoring limit/offset
+    pub total: u64,
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            ProjectStatus::InProgress => "InProgress",
+            ProjectStatus::Completed => "Completed",
+            ProjectStatus::InitialStage => "InitialStage",
+            ProjectStatus::OnHold => "OnHold",
+            ProjectStatus::Abandoned => "Abandoned",
+        };
+        f.write_str(status)
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "InProgress" => Ok(ProjectStatus::InProgress),
+            "Completed" => Ok(ProjectStatus::Completed),
+            "InitialStage" => Ok(ProjectStatus::InitialStage),
+            "OnHold" => Ok(ProjectStatus::OnHold),
+            "Abandoned" => Ok(ProjectStatus::Abandoned),
+            _ => Err(format!("Unknown project status: {}", s)),
+        }
+    }
+}
+
 impl Project {
     pub fn new(name: String, description: String, location: String, status: ProjectStatus) -> Self {
         let now = Utc::now().to_rfc3339();
+        let location_exists = Path::new(&location).is_dir();
+        let git_branch = read_git_branch(Path::new(&location));
         Project {
             id: Uuid::new_v4().to_string(),
             name,
@@ -35,6 +117,44 @@ impl Project {
             created_at: now.clone(),
             updated_at: now,
             db_config: None,
+            deleted_at: None,
+            is_favorite: false,
+            location_exists,
+            git_branch,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_STATUSES: [ProjectStatus; 5] = [
+        ProjectStatus::InProgress,
+        ProjectStatus::Completed,
+        ProjectStatus::InitialStage,
+        ProjectStatus::OnHold,
+        ProjectStatus::Abandoned,
+    ];
+
+    #[test]
+    fn status_round_trips_through_its_stored_string() {
+        for status in ALL_STATUSES {
+            let stored = status.to_string();
+            let parsed: ProjectStatus = stored.parse().unwrap();
+            assert_eq!(parsed.to_string(), stored);
+
+            // The frontend sends and receives the same names through serde
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", stored));
+            let parsed: ProjectStatus = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.to_string(), stored);
+        }
+    }
+
+    #[test]
+    fn unknown_status_is_an_error() {
+        assert!("Archived".parse::<ProjectStatus>().is_err());
+        assert!("inprogress".parse::<ProjectStatus>().is_err());
+    }
+}
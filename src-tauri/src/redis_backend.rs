@@ -0,0 +1,229 @@
+use crate::db_factory::KeyValueBackend;
+use crate::models::db_types::{DbCredentials, RedisData, RedisKeys, RedisValue};
+use redis::{Cmd, ConnectionAddr, ConnectionInfo, RedisConnectionInfo};
+use std::time::Duration;
+
+/// Number of databases assumed when the server won't say (`CONFIG` is often disabled on
+/// managed Redis); 16 is the redis.conf default.
+const DEFAULT_REDIS_DATABASES: i64 = 16;
+
+/// Most keys `keys` returns for one pattern.
+const REDIS_KEY_LIMIT: usize = 1000;
+
+/// Most elements read from a list, set, sorted set or hash.
+const REDIS_VALUE_LIMIT: usize = 1000;
+
+/// `COUNT` hint passed to each `SCAN`/`SSCAN`/`HSCAN` round trip.
+const SCAN_BATCH: usize = 500;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Redis has no tables or SQL, so it only implements `KeyValueBackend`; its numbered
+/// databases are listed in place of tables.
+pub struct RedisBackend {
+    conn: redis::Connection,
+    database: i64,
+    databases: i64, // How many numbered databases the server has
+}
+
+impl RedisBackend {
+    pub fn new(creds: &DbCredentials) -> Result<Self, String> {
+        let database = match creds.database.trim() {
+            "" => 0,
+            database => database
+                .parse()
+                .map_err(|_| format!("Invalid Redis database number '{}'", database))?,
+        };
+        let socket = creds.socket.clone().filter(|s| !s.trim().is_empty());
+        let addr = match socket {
+            Some(socket) => ConnectionAddr::Unix(socket.into()),
+            None => ConnectionAddr::Tcp(
+                creds
+                    .host
+                    .clone()
+                    .filter(|h| !h.is_empty())
+                    .unwrap_or("127.0.0.1".to_string()),
+                creds
+                    .port
+                    .clone()
+                    .unwrap_or("6379".to_string())
+                    .parse()
+                    .map_err(|e| format!("Invalid port number: {}", e))?,
+            ),
+        };
+        let info = ConnectionInfo {
+            addr,
+            redis: RedisConnectionInfo {
+                db: database,
+                // Redis without ACLs only takes a password
+                username: creds.username.clone().filter(|u| !u.is_empty()),
+                password: creds.password.clone().filter(|p| !p.is_empty()),
+                ..Default::default()
+            },
+        };
+
+        let conn = redis::Client::open(info)
+            .and_then(|client| client.get_connection_with_timeout(CONNECT_TIMEOUT))
+            .map_err(|e| format!("Failed to connect to Redis: {}", e))?;
+        let mut backend = Self {
+            conn,
+            database,
+            databases: DEFAULT_REDIS_DATABASES,
+        };
+        backend.databases = backend.database_count();
+        Ok(backend)
+    }
+
+    /// The server's `databases` setting, or the default if `CONFIG GET` isn't allowed.
+    fn database_count(&mut self) -> i64 {
+        // Replies with the name and value, e.g. ["databases", "16"]
+        self.query::<Vec<String>>(redis::cmd("CONFIG").arg("GET").arg("databases"))
+            .ok()
+            .and_then(|reply| reply.get(1)?.parse().ok())
+            .filter(|count| *count > 0)
+            .unwrap_or(DEFAULT_REDIS_DATABASES)
+    }
+
+    fn query<T: redis::FromRedisValue>(&mut self, cmd: &Cmd) -> Result<T, String> {
+        cmd.query(&mut self.conn).map_err(|e| e.to_string())
+    }
+
+    fn select(&mut self, database: Option<i64>) -> Result<(), String> {
+        let database = database.unwrap_or(self.database);
+        if !(0..self.databases).contains(&database) {
+            return Err(format!("Invalid Redis database number {}", database));
+        }
+        self.query(redis::cmd("SELECT").arg(database))
+    }
+
+    /// Runs a cursor command (`SCAN`, or `SSCAN`/`HSCAN` when `key` is given) until the
+    /// cursor wraps or `limit` entries were collected. Returns the entries and whether
+    /// the scan stopped early.
+    fn scan(
+        &mut self,
+        command: &str,
+        key: Option<&str>,
+        pattern: &str,
+        limit: usize,
+    ) -> Result<(Vec<String>, bool), String> {
+        let mut entries = Vec::new();
+        let mut cursor = 0u64;
+        loop {
+            let mut cmd = redis::cmd(command);
+            if let Some(key) = key {
+                cmd.arg(key);
+            }
+            cmd.arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH);
+            let (next, batch): (u64, Vec<Vec<u8>>) = self.query(&cmd)?;
+            entries.extend(batch.iter().map(|b| String::from_utf8_lossy(b).to_string()));
+            cursor = next;
+            if cursor == 0 || entries.len() >= limit {
+                break;
+            }
+        }
+        let truncated = cursor != 0 || entries.len() > limit;
+        entries.truncate(limit);
+        Ok((entries, truncated))
+    }
+
+    fn strings(values: Vec<Vec<u8>>) -> Vec<String> {
+        values
+            .iter()
+            .map(|v| String::from_utf8_lossy(v).to_string())
+            .collect()
+    }
+}
+
+impl KeyValueBackend for RedisBackend {
+    /// `0` up to the server's `databases` setting, usually `15`.
+    fn databases(&mut self) -> Vec<String> {
+        (0..self.databases).map(|db| db.to_string()).collect()
+    }
+
+    fn keys(&mut self, database: Option<i64>, pattern: &str) -> Result<RedisKeys, String> {
+        self.select(database)?;
+        let pattern = if pattern.is_empty() { "*" } else { pattern };
+        let (mut keys, truncated) = self.scan("SCAN", None, pattern, REDIS_KEY_LIMIT)?;
+        keys.sort();
+        Ok(RedisKeys { keys, truncated })
+    }
+
+    fn get(&mut self, database: Option<i64>, key: &str) -> Result<RedisValue, String> {
+        self.select(database)?;
+        let key_type: String = self.query(redis::cmd("TYPE").arg(key))?;
+        let last = REDIS_VALUE_LIMIT as i64 - 1;
+
+        let (data, length) = match key_type.as_str() {
+            "none" => (RedisData::None, 0),
+            "string" => {
+                let value: Vec<u8> = self.query(redis::cmd("GET").arg(key))?;
+                let length = value.len() as u64;
+                (
+                    RedisData::String(String::from_utf8_lossy(&value).to_string()),
+                    length,
+                )
+            }
+            "list" => {
+                let values = self.query(redis::cmd("LRANGE").arg(key).arg(0).arg(last))?;
+                let length = self.query(redis::cmd("LLEN").arg(key))?;
+                (RedisData::List(Self::strings(values)), length)
+            }
+            "set" => {
+                let (members, _) = self.scan("SSCAN", Some(key), "*", REDIS_VALUE_LIMIT)?;
+                let length = self.query(redis::cmd("SCARD").arg(key))?;
+                (RedisData::Set(members), length)
+            }
+            "zset" => {
+                let flat: Vec<Vec<u8>> = self.query(
+                    redis::cmd("ZRANGE")
+                        .arg(key)
+                        .arg(0)
+                        .arg(last)
+                        .arg("WITHSCORES"),
+                )?;
+                let members = flat
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let score = String::from_utf8_lossy(&pair[1]);
+                        // Scores come back as text; "inf"/"-inf" parse as infinities
+                        let score = score
+                            .parse()
+                            .map_err(|_| format!("Invalid sorted set score '{}'", score))?;
+                        Ok((String::from_utf8_lossy(&pair[0]).to_string(), score))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                let length = self.query(redis::cmd("ZCARD").arg(key))?;
+                (RedisData::ZSet(members), length)
+            }
+            "hash" => {
+                // HSCAN returns fields and values interleaved, so collect twice the limit
+                let (flat, _) = self.scan("HSCAN", Some(key), "*", REDIS_VALUE_LIMIT * 2)?;
+                let fields = flat
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect();
+                let length = self.query(redis::cmd("HLEN").arg(key))?;
+                (RedisData::Hash(fields), length)
+            }
+            other => (RedisData::Other(other.to_string()), 0),
+        };
+
+        // TTL is -1 for keys without an expiry and -2 for keys that don't exist
+        let ttl: i64 = self.query(redis::cmd("TTL").arg(key))?;
+        Ok(RedisValue {
+            key: key.to_string(),
+            data,
+            length,
+            ttl: Some(ttl).filter(|ttl| *ttl >= 0),
+        })
+    }
+
+    fn key_type(&mut self, database: Option<i64>, key: &str) -> Result<String, String> {
+        self.select(database)?;
+        self.query(redis::cmd("TYPE").arg(key))
+    }
+}
@@ -1,20 +1,153 @@
-use crate::db_factory::DbBackend;
-use portable_pty::MasterPty;
-use std::collections::HashMap;
+use crate::db_factory::{Backend, QueryCanceller};
+use crate::models::db_types::{ConnectionInfo, DbCredentials};
+use portable_pty::{Child, MasterPty};
+use std::collections::{HashMap, VecDeque};
 use std::io::Write;
-use std::sync::{mpsc::Sender, Mutex};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc::Sender, Arc, Mutex};
+use std::time::SystemTime;
 
 pub struct DbConnectionManager {
-    pub connections: Mutex<HashMap<String, Box<dyn DbBackend + Send>>>,
+    pub connections: Mutex<HashMap<String, CachedConnection>>,
+    /// Resolved credentials per project, so lookups such as the connection type don't
+    /// re-read `.env` every time.
+    pub credentials: Mutex<HashMap<String, CachedCredentials>>,
+}
+
+/// A backend shared between the connection cache and the command currently using it.
+pub type SharedBackend = Arc<Mutex<Backend>>;
+
+/// An open backend for a project, plus what's needed to notice its `.env` changing.
+pub struct CachedConnection {
+    pub backend: SharedBackend,
+    /// Usable while `backend` is locked by a running query; `None` for Redis.
+    pub canceller: Option<Arc<dyn QueryCanceller>>,
+    pub info: ConnectionInfo,
+    pub env: EnvStamp,
+}
+
+#[derive(Clone)]
+pub struct CachedCredentials {
+    pub credentials: DbCredentials,
+    pub location: String, // Project directory, which relative SQLite paths resolve against
+    pub env: EnvStamp,
+}
+
+/// Modification time of a project's `.env` when something was derived from it.
+#[derive(Clone)]
+pub struct EnvStamp {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl EnvStamp {
+    pub fn new(path: PathBuf) -> Self {
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        Self { path, modified }
+    }
+
+    /// Whether the file was edited, created or removed since the stamp was taken.
+    pub fn is_stale(&self) -> bool {
+        std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok()
+            != self.modified
+    }
 }
 
 pub struct TerminalSession {
     pub writer: Box<dyn Write + Send>,
     pub master: Box<dyn MasterPty + Send>,
+    pub child: Box<dyn Child + Send + Sync>,
+    pub cwd: Option<String>, // Directory the shell was spawned in
+    // Shared with the reader thread, which appends output as it emits it
+    pub scrollback: Arc<Mutex<Scrollback>>,
+}
+
+/// Ring buffer of the most recent PTY output, so a reloaded frontend can repaint history.
+pub struct Scrollback {
+    buffer: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl Scrollback {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.buffer.len() + bytes.len()).saturating_sub(self.capacity);
+        self.buffer.drain(..overflow);
+        self.buffer.extend(bytes);
+    }
+
+    pub fn contents(&self) -> String {
+        let (front, back) = self.buffer.as_slices();
+        String::from_utf8_lossy(&[front, back].concat()).to_string()
+    }
+}
+
+/// A long-running process started from the app for a project, e.g. `php artisan serve`,
+/// `queue:work` or `npm run dev`.
+pub struct TrackedProcess {
+    pub label: String,
+    pub child: std::process::Child,
+    pub port: Option<u16>,
+    pub started_at: String,
+}
+
+/// Processes started per project, so they can be listed, stopped, and cleaned up when the app
+/// exits instead of being orphaned.
+#[derive(Default)]
+pub struct ProcessRegistry {
+    // Map of project ID to its processes; labels are unique within a project
+    processes: Mutex<HashMap<String, Vec<TrackedProcess>>>,
+}
+
+impl ProcessRegistry {
+    /// Runs `f` with the project's processes.
+    pub fn with_project<T>(
+        &self,
+        project_id: &str,
+        f: impl FnOnce(&mut Vec<TrackedProcess>) -> T,
+    ) -> T {
+        let mut processes = self.processes.lock().unwrap();
+        let tracked = processes.entry(project_id.to_string()).or_default();
+        let result = f(tracked);
+        if tracked.is_empty() {
+            processes.remove(project_id);
+        }
+        result
+    }
+
+    pub fn remove(&self, project_id: &str, label: &str) -> Option<TrackedProcess> {
+        self.with_project(project_id, |tracked| {
+            let index = tracked.iter().position(|p| p.label == label)?;
+            Some(tracked.remove(index))
+        })
+    }
+
+    /// Removes every process, for killing on shutdown.
+    pub fn drain(&self) -> Vec<TrackedProcess> {
+        self.processes
+            .lock()
+            .unwrap()
+            .drain()
+            .flat_map(|(_, tracked)| tracked)
+            .collect()
+    }
 }
 
 pub struct AppState {
     pub project_event_tx: Mutex<Sender<String>>,
     // Map of window/tab ID to TerminalSession
     pub terminal_sessions: Mutex<HashMap<String, TerminalSession>>,
+    pub processes: ProcessRegistry,
+    // Map of project ID to the stop flag of its log watcher thread
+    pub log_watchers: Mutex<HashMap<String, Arc<AtomicBool>>>,
 }
@@ -1,9 +1,10 @@
-use std::path::PathBuf;
+use crate::database::Database;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
-/// Get the absolute path to the projects database file.
-/// This ensures the database is stored in a persistent location
-/// that works in both development and production builds.
-pub fn get_db_path() -> Result<PathBuf, String> {
+/// Get the app's data directory, creating it if needed.
+pub fn get_app_data_dir() -> Result<PathBuf, String> {
     // Get the app data directory
     let app_data_dir =
         dirs::data_local_dir().ok_or_else(|| "Failed to get app data directory".to_string())?;
@@ -15,6 +16,233 @@ pub fn get_db_path() -> Result<PathBuf, String> {
     std::fs::create_dir_all(&workshop_dir)
         .map_err(|e| format!("Failed to create app data directory: {}", e))?;
 
-    // Return the full path to the database file
-    Ok(workshop_dir.join("projects.db"))
+    Ok(workshop_dir)
+}
+
+/// Get the absolute path to the projects database file.
+/// This ensures the database is stored in a persistent location
+/// that works in both development and production builds.
+pub fn get_db_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("projects.db"))
+}
+
+/// Folder of the project with the given id.
+pub(crate) fn project_location(project_id: &str) -> Result<PathBuf, String> {
+    let db = Database::new(get_db_path()?).map_err(|e| e.to_string())?;
+    let project = db
+        .get_project_by_id(project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Project not found".to_string())?;
+    Ok(PathBuf::from(project.location))
+}
+
+/// Current branch of the git repository at `location`, read straight from `.git/HEAD` so it's
+/// cheap enough to do for every project. `None` for a detached HEAD or a non-git folder.
+pub fn read_git_branch(location: &Path) -> Option<String> {
+    let mut git_dir = location.join(".git");
+    // Worktrees and submodules have a `.git` file pointing at the real git directory
+    if git_dir.is_file() {
+        let pointer = std::fs::read_to_string(&git_dir).ok()?;
+        let target = pointer.trim().strip_prefix("gitdir:")?.trim();
+        git_dir = location.join(target);
+    }
+
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(|branch| branch.to_string())
+}
+
+/// Parse the contents of a `.env` file into key/value pairs.
+/// Handles blank and `#` comment lines, an optional `export ` prefix, single and
+/// double quoted values (which may span several lines, e.g. PEM keys), and trailing
+/// `# comments` after unquoted values.
+pub fn parse_env(content: &str) -> HashMap<String, String> {
+    parse_env_entries(content)
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect()
+}
+
+/// A variable in a `.env` file, with the range of (0-based) lines it occupies.
+pub struct EnvEntry {
+    pub key: String,
+    pub value: String,
+    pub lines: Range<usize>,
+}
+
+/// Like `parse_env`, but keeps every definition in file order along with where it is.
+pub fn parse_env_entries(content: &str) -> Vec<EnvEntry> {
+    let mut entries = Vec::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((start, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+
+        // Keep joining lines until an opened quote is closed
+        let mut raw = value.trim().to_string();
+        let mut end = start + 1;
+        let value = loop {
+            let (parsed, closed) = parse_env_value(&raw);
+            if closed {
+                break parsed;
+            }
+            match lines.next() {
+                Some((_, next)) => {
+                    raw.push('\n');
+                    raw.push_str(next);
+                    end += 1;
+                }
+                None => break parsed,
+            }
+        };
+
+        entries.push(EnvEntry {
+            key: key.to_string(),
+            value,
+            lines: start..end,
+        });
+    }
+
+    entries
+}
+
+/// Returns the parsed value and whether any opening quote was closed.
+fn parse_env_value(value: &str) -> (String, bool) {
+    let mut chars = value.chars();
+    match chars.next() {
+        // Single quotes are literal; double quotes allow backslash escapes
+        Some(quote @ ('"' | '\'')) => {
+            let mut parsed = String::new();
+            while let Some(c) = chars.next() {
+                if c == quote {
+                    return (parsed, true);
+                }
+                if c == '\\' && quote == '"' {
+                    match chars.next() {
+                        Some('n') => parsed.push('\n'),
+                        Some('r') => parsed.push('\r'),
+                        Some('t') => parsed.push('\t'),
+                        Some(other @ ('"' | '\\' | '$')) => parsed.push(other),
+                        Some(other) => {
+                            parsed.push('\\');
+                            parsed.push(other);
+                        }
+                        None => parsed.push('\\'),
+                    }
+                } else {
+                    parsed.push(c);
+                }
+            }
+            (parsed, false)
+        }
+        // A `#` only starts a comment at the start or after whitespace, so `pa#ss` survives
+        _ => {
+            let end = value
+                .char_indices()
+                .find(|&(i, c)| {
+                    c == '#' && (i == 0 || value[..i].ends_with(|p: char| p.is_whitespace()))
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(value.len());
+            (value[..end].trim_end().to_string(), true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_value(content: &str, key: &str) -> Option<String> {
+        parse_env(content).remove(key)
+    }
+
+    #[test]
+    fn env_double_quotes_are_stripped() {
+        assert_eq!(
+            env_value("APP_NAME=\"My App\"", "APP_NAME").as_deref(),
+            Some("My App")
+        );
+    }
+
+    #[test]
+    fn env_single_quotes_are_literal() {
+        assert_eq!(
+            env_value(r"DB_PASSWORD='p@ss\n#1'", "DB_PASSWORD").as_deref(),
+            Some(r"p@ss\n#1")
+        );
+    }
+
+    #[test]
+    fn env_export_prefix_is_ignored() {
+        assert_eq!(
+            env_value("export DB_HOST=127.0.0.1", "DB_HOST").as_deref(),
+            Some("127.0.0.1")
+        );
+    }
+
+    #[test]
+    fn env_inline_comments_end_unquoted_values() {
+        let env =
+            parse_env("DB_PORT=3306 # default\nDB_PASSWORD=pa#ss\nAPP_URL=\"http://x # y\" # note");
+        assert_eq!(env["DB_PORT"], "3306");
+        assert_eq!(env["DB_PASSWORD"], "pa#ss");
+        assert_eq!(env["APP_URL"], "http://x # y");
+    }
+
+    #[test]
+    fn env_double_quoted_escapes_are_decoded() {
+        assert_eq!(
+            env_value(r#"MESSAGE="say \"hi\"\tto \\ \$HOME\q""#, "MESSAGE").as_deref(),
+            Some("say \"hi\"\tto \\ $HOME\\q")
+        );
+    }
+
+    #[test]
+    fn env_empty_values_are_kept() {
+        let env = parse_env("DB_PASSWORD=\nMAIL_FROM=\"\"\nREDIS_PASSWORD=  # none");
+        assert_eq!(env["DB_PASSWORD"], "");
+        assert_eq!(env["MAIL_FROM"], "");
+        assert_eq!(env["REDIS_PASSWORD"], "");
+    }
+
+    #[test]
+    fn env_blank_comment_and_malformed_lines_are_skipped() {
+        let env = parse_env("\n# DB_HOST=commented\n   \nNOT_A_VARIABLE\n=no_key\nDB_HOST=db\n");
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["DB_HOST"], "db");
+    }
+
+    #[test]
+    fn env_double_quoted_values_can_span_lines() {
+        let content = "APP_NAME=Shop\nJWT_PUBLIC_KEY=\"-----BEGIN PUBLIC KEY-----\nMIIBIjANBg\n-----END PUBLIC KEY-----\"\nDB_HOST=db\n";
+        let entries = parse_env_entries(content);
+        let key = entries
+            .iter()
+            .find(|entry| entry.key == "JWT_PUBLIC_KEY")
+            .unwrap();
+        assert_eq!(
+            key.value,
+            "-----BEGIN PUBLIC KEY-----\nMIIBIjANBg\n-----END PUBLIC KEY-----"
+        );
+        assert_eq!(key.lines, 1..4);
+
+        // Lines after the closing quote are parsed normally
+        let env = parse_env(content);
+        assert_eq!(env["DB_HOST"], "db");
+        assert_eq!(env.len(), 3);
+    }
 }
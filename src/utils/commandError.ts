@@ -0,0 +1,20 @@
+/**
+ * Error returned by backend commands; `code` is stable, `message` is for display
+ */
+export interface CommandError {
+    code: 'NotFound' | 'DbConnection' | 'Database' | 'Io' | 'InvalidInput' | 'Unsupported' | 'Other';
+    message: string;
+}
+
+export const isCommandError = (error: unknown): error is CommandError =>
+    typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+
+/**
+ * Extracts a displayable message from anything a command can throw
+ */
+export const describeError = (error: unknown, fallback?: string): string => {
+    if (isCommandError(error) || error instanceof Error) {
+        return error.message;
+    }
+    return fallback ?? String(error);
+};